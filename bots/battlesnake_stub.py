@@ -0,0 +1,50 @@
+#!/usr/bin/env python3
+# Minimal Battlesnake server for testing the HTTP adapter: `python3 bots/battlesnake_stub.py 8000`
+# then `--bot http://localhost:8000`. Heads for the food using Battlesnake's bottom-left
+# origin, where "up" increases y, and logs every request it receives to stderr.
+import json
+import sys
+from http.server import BaseHTTPRequestHandler, HTTPServer
+
+MOVES = {"up": (0, 1), "down": (0, -1), "left": (-1, 0), "right": (1, 0)}
+
+
+def choose(state):
+    board = state["board"]
+    head = state["you"]["head"]
+    food = board["food"][0]
+    taken = {(p["x"], p["y"]) for s in board["snakes"] for p in s["body"]}
+    taken |= {(p["x"], p["y"]) for p in board["hazards"]}
+
+    def score(move):
+        dx, dy = MOVES[move]
+        x, y = (head["x"] + dx) % board["width"], (head["y"] + dy) % board["height"]
+        return ((x, y) in taken, abs(food["x"] - x) + abs(food["y"] - y))
+
+    return min(MOVES, key=score)
+
+
+class Handler(BaseHTTPRequestHandler):
+    def do_GET(self):
+        self.reply({"apiversion": "1", "author": "stub"})
+
+    def do_POST(self):
+        state = json.loads(self.rfile.read(int(self.headers["Content-Length"])))
+        sys.stderr.write("%s %s turn %d\n" % (self.path, state["game"]["id"], state["turn"]))
+        self.reply({"move": choose(state)} if self.path.endswith("/move") else {})
+
+    def reply(self, body):
+        data = json.dumps(body).encode()
+        self.send_response(200)
+        self.send_header("Content-Type", "application/json")
+        self.send_header("Content-Length", str(len(data)))
+        self.end_headers()
+        self.wfile.write(data)
+
+    def log_message(self, *args):
+        pass
+
+
+if __name__ == "__main__":
+    port = int(sys.argv[1]) if len(sys.argv) > 1 else 8000
+    HTTPServer(("127.0.0.1", port), Handler).serve_forever()
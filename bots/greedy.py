@@ -0,0 +1,22 @@
+#!/usr/bin/env python3
+# Example snake bot: heads straight for the food, avoiding cells any snake occupies.
+# Reads one JSON state per line on stdin and answers with a direction per line.
+import json
+import sys
+
+MOVES = {"up": (0, -1), "down": (0, 1), "left": (-1, 0), "right": (1, 0)}
+
+for line in sys.stdin:
+    state = json.loads(line)
+    width, height = state["width"], state["height"]
+    head = state["snakes"][0]["head"]
+    food = state["food"][0]
+    taken = {(p["x"], p["y"]) for s in state["snakes"] for p in [s["head"]] + s["body"]}
+
+    def score(move):
+        dx, dy = MOVES[move]
+        x, y = (head["x"] + dx) % width, (head["y"] + dy) % height
+        blocked = (x, y) in taken
+        return (blocked, abs(food["x"] - x) + abs(food["y"] - y))
+
+    print(min(MOVES, key=score), flush=True)
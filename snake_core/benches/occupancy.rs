@@ -0,0 +1,50 @@
+use std::{collections::LinkedList, hint::black_box, time::Instant};
+
+use snake_core::{OccupancyGrid, Position};
+
+const BOARD: (i16, i16) = (1000, 1000);
+const LENGTH: usize = 250_000;
+const QUERIES: usize = 2_000;
+
+fn serpentine(length: usize) -> impl Iterator<Item = Position> {
+    (0..length).map(|i| {
+        let y = (i / BOARD.0 as usize) as i16;
+        let x = (i % BOARD.0 as usize) as i16;
+        Position::new(if y % 2 == 0 { x } else { BOARD.0 - 1 - x }, y)
+    })
+}
+
+fn queries() -> Vec<Position> {
+    let mut rng = oorandom::Rand32::new(7);
+    (0..QUERIES).map(|_| Position::random(&mut rng, BOARD.0, BOARD.1)).collect()
+}
+
+fn bench(name: &str, mut f: impl FnMut() -> usize) {
+    let start = Instant::now();
+    let hits = black_box(f());
+    let elapsed = start.elapsed();
+    println!("{:<28} {:>10.3} ms  ({} hits)", name, elapsed.as_secs_f64() * 1000.0, hits);
+}
+
+fn main() {
+    let body: LinkedList<Position> = serpentine(LENGTH).collect();
+    let mut grid = OccupancyGrid::new(BOARD.0, BOARD.1);
+    serpentine(LENGTH).for_each(|pos| grid.insert(pos));
+    let queries = queries();
+
+    println!("{}x{} board, snake length {}, {} queries", BOARD.0, BOARD.1, LENGTH, QUERIES);
+
+    bench("collision: linked list scan", || queries.iter().filter(|&&pos| body.iter().any(|&segment| segment == pos)).count());
+    bench("collision: occupancy grid", || queries.iter().filter(|&&pos| grid.contains(pos)).count());
+
+    bench("free cell: rebuilt grid", || {
+        (0..20)
+            .filter_map(|nth| {
+                let mut occupied = vec![false; BOARD.0 as usize * BOARD.1 as usize];
+                body.iter().for_each(|pos| occupied[pos.y as usize * BOARD.0 as usize + pos.x as usize] = true);
+                occupied.iter().enumerate().filter(|(_, &taken)| !taken).nth(nth * 1000).map(|(index, _)| index)
+            })
+            .count()
+    });
+    bench("free cell: occupancy grid", || (0..20).filter_map(|nth| grid.nth_free(nth * 1000)).count());
+}
@@ -0,0 +1,281 @@
+use std::{collections::VecDeque, fmt, str::FromStr};
+
+use crate::{Boundary, Direction, Food, Hamiltonian, OccupancyGrid, Position, Snake};
+
+/// Everything a controller may look at when choosing its next move.
+pub struct View<'a> {
+    pub boundary: &'a Boundary,
+    pub obstacles: &'a OccupancyGrid,
+    pub snake: &'a Snake,
+    pub others: Vec<&'a Snake>,
+    pub food: &'a Food,
+    /// Ticks played so far in the game being viewed.
+    pub tick: u64,
+}
+
+impl View<'_> {
+    pub fn board(&self) -> (i16, i16) {
+        (self.obstacles.width(), self.obstacles.height())
+    }
+}
+
+/// Steers a snake the same way a player does, by asking for a turn before each tick.
+pub trait Controller {
+    /// Returns the direction to turn towards, or `None` to keep going straight.
+    fn steer(&mut self, view: &View) -> Option<Direction>;
+
+    /// Called once the game is over or abandoned, with the final board. May be called
+    /// more than once for the same game.
+    fn end(&mut self, _view: &View) {}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ai {
+    Autopilot,
+    Hamiltonian,
+}
+
+impl Ai {
+    pub const ALL: [Ai; 2] = [Ai::Autopilot, Ai::Hamiltonian];
+
+    pub fn controller(&self) -> Box<dyn Controller + Send> {
+        match self {
+            Ai::Autopilot => Box::new(Autopilot),
+            Ai::Hamiltonian => Box::new(Hamiltonian::default()),
+        }
+    }
+}
+
+impl fmt::Display for Ai {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Ai::Autopilot => "autopilot",
+            Ai::Hamiltonian => "hamiltonian",
+        })
+    }
+}
+
+impl FromStr for Ai {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ai::ALL.into_iter().find(|ai| ai.to_string() == s).ok_or(())
+    }
+}
+
+/// Takes the shortest path to the food as long as the tail is still reachable once
+/// it gets there. Otherwise it follows its own tail, and failing that moves towards
+/// the largest open area.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Autopilot;
+
+impl Controller for Autopilot {
+    fn steer(&mut self, view: &View) -> Option<Direction> {
+        let body: VecDeque<Position> = view.snake.segments().map(|segment| segment.0).collect();
+        let tail = *body.back()?;
+
+        if let Some(path) = shortest_path(view, &body, view.food.pos) {
+            if let Some(after) = follow(view, &body, &path) {
+                if shortest_path(view, &after, *after.back()?).is_some() {
+                    return path.first().copied();
+                }
+            }
+        }
+
+        if let Some(path) = shortest_path(view, &body, tail) {
+            return path.first().copied();
+        }
+
+        let free_at = free_at(view, &body);
+        Direction::ALL
+            .into_iter()
+            .filter_map(|dir| {
+                let next = view.boundary.advance(body[0], dir, view.board())?;
+                (free_at[index(view, next)] <= 1).then(|| (open_area(view, &free_at, next), dir))
+            })
+            .max_by_key(|(area, _)| *area)
+            .map(|(_, dir)| dir)
+    }
+}
+
+fn index(view: &View, pos: Position) -> usize {
+    pos.y as usize * view.board().0 as usize + pos.x as usize
+}
+
+/// The first move on which each cell can be entered. A body segment `i` cells from
+/// the head of a snake of length `n` moves away after `n - i` moves, and the cell can
+/// be entered on the move after that, since moving onto the current tail is a crash.
+fn free_at(view: &View, body: &VecDeque<Position>) -> Vec<u32> {
+    let (width, height) = view.board();
+    let mut free_at = vec![0; width as usize * height as usize];
+
+    for y in 0..height {
+        for x in 0..width {
+            let pos = Position::new(x, y);
+            if view.obstacles.contains(pos) {
+                free_at[index(view, pos)] = u32::MAX;
+            }
+        }
+    }
+
+    let others = view.others.iter().map(|snake| snake.segments().map(|segment| segment.0).collect::<Vec<_>>());
+    for segments in std::iter::once(body.iter().copied().collect()).chain(others) {
+        let length = segments.len() as u32;
+        for (i, pos) in segments.into_iter().enumerate() {
+            let cell = &mut free_at[index(view, pos)];
+            *cell = (*cell).max(length - i as u32 + 1);
+        }
+    }
+    free_at
+}
+
+/// Breadth-first search from the head of `body` to `target`, respecting the board
+/// boundary and when each body cell clears.
+fn shortest_path(view: &View, body: &VecDeque<Position>, target: Position) -> Option<Vec<Direction>> {
+    let board = view.board();
+    let free_at = free_at(view, body);
+    let mut came_from: Vec<Option<(Direction, Position)>> = vec![None; free_at.len()];
+    let mut distance = vec![u32::MAX; free_at.len()];
+
+    let start = *body.front()?;
+    distance[index(view, start)] = 0;
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(pos) = queue.pop_front() {
+        if pos == target && pos != start {
+            break;
+        }
+        let next_distance = distance[index(view, pos)] + 1;
+        for dir in Direction::ALL {
+            let next = match view.boundary.advance(pos, dir, board) {
+                Some(next) => next,
+                None => continue,
+            };
+            let cell = index(view, next);
+            if distance[cell] != u32::MAX || free_at[cell] > next_distance {
+                continue;
+            }
+            distance[cell] = next_distance;
+            came_from[cell] = Some((dir, pos));
+            queue.push_back(next);
+        }
+    }
+
+    let mut path = Vec::new();
+    let mut pos = target;
+    while pos != start || path.is_empty() {
+        let (dir, prev) = came_from[index(view, pos)]?;
+        path.push(dir);
+        pos = prev;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// The body after walking `path`, growing on the last move where the food is.
+fn follow(view: &View, body: &VecDeque<Position>, path: &[Direction]) -> Option<VecDeque<Position>> {
+    let mut body = body.clone();
+    for (i, &dir) in path.iter().enumerate() {
+        let head = view.boundary.advance(*body.front()?, dir, view.board())?;
+        body.push_front(head);
+        if i + 1 < path.len() {
+            body.pop_back();
+        }
+    }
+    Some(body)
+}
+
+fn open_area(view: &View, free_at: &[u32], from: Position) -> usize {
+    let mut seen = vec![false; free_at.len()];
+    seen[index(view, from)] = true;
+    let mut queue = VecDeque::from([from]);
+    let mut area = 0;
+
+    while let Some(pos) = queue.pop_front() {
+        area += 1;
+        for dir in Direction::ALL {
+            if let Some(next) = view.boundary.advance(pos, dir, view.board()) {
+                let cell = index(view, next);
+                if !seen[cell] && free_at[cell] == 0 {
+                    seen[cell] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FoodKind;
+
+    const BOARD: (i16, i16) = (10, 10);
+
+    fn view<'a>(boundary: &'a Boundary, obstacles: &'a OccupancyGrid, snake: &'a Snake, food: &'a Food) -> View<'a> {
+        View { boundary, obstacles, snake, others: Vec::new(), food, tick: 0 }
+    }
+
+    fn body(snake: &Snake) -> VecDeque<Position> {
+        snake.segments().map(|segment| segment.0).collect()
+    }
+
+    #[test]
+    fn takes_the_short_way_across_a_wrapping_edge() {
+        let snake = Snake::new(Position::new(1, 5), Direction::Left, BOARD, 3);
+        let food = Food::new(Position::new(8, 5), FoodKind::Normal);
+        let obstacles = OccupancyGrid::new(BOARD.0, BOARD.1);
+        let boundary = Boundary::wrap();
+        let view = view(&boundary, &obstacles, &snake, &food);
+
+        let path = shortest_path(&view, &body(&snake), food.pos).unwrap();
+        assert_eq!(path, [Direction::Left; 3]);
+        assert_eq!(Autopilot.steer(&view), Some(Direction::Left));
+    }
+
+    #[test]
+    fn goes_around_a_walled_edge() {
+        let snake = Snake::new(Position::new(1, 5), Direction::Left, BOARD, 3);
+        let food = Food::new(Position::new(8, 5), FoodKind::Normal);
+        let obstacles = OccupancyGrid::new(BOARD.0, BOARD.1);
+        let boundary: Boundary = "l".parse().unwrap();
+        let view = view(&boundary, &obstacles, &snake, &food);
+
+        // Up or down, seven cells right and back, since the tail blocks the way right.
+        let path = shortest_path(&view, &body(&snake), food.pos).unwrap();
+        assert_eq!(path.len(), 9);
+        assert!(matches!(Autopilot.steer(&view), Some(Direction::Up | Direction::Down)));
+    }
+
+    #[test]
+    fn follows_its_tail_when_the_food_is_walled_off() {
+        let mut obstacles = OccupancyGrid::new(BOARD.0, BOARD.1);
+        for wall in [(7, 5), (9, 5), (8, 4), (8, 6)] {
+            obstacles.insert(wall.into());
+        }
+        let food = Food::new(Position::new(8, 5), FoodKind::Normal);
+        let boundary = Boundary::walls();
+
+        let mut snake = Snake::new(Position::new(2, 2), Direction::Right, BOARD, 3);
+        for _ in 0..4 {
+            let ahead = Food::new(Position::new_from_move(snake.head.0, snake.dir, BOARD), FoodKind::Normal);
+            snake.update(&ahead, &boundary, &obstacles);
+        }
+        assert_eq!(snake.length(), 6);
+
+        for tick in 0..200 {
+            let view = view(&boundary, &obstacles, &snake, &food);
+            assert_eq!(shortest_path(&view, &body(&snake), food.pos), None);
+            let to_tail = shortest_path(&view, &body(&snake), *body(&snake).back().unwrap()).unwrap();
+            let dir = Autopilot.steer(&view);
+            assert_eq!(dir, to_tail.first().copied(), "tick {}", tick);
+
+            if let Some(dir) = dir {
+                snake.turn(dir);
+            }
+            snake.update(&food, &boundary, &obstacles);
+            assert_eq!(snake.touched, None, "crashed on tick {}", tick);
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    process,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use crate::{Controller, Direction, Edge, Position, Snake, View};
+
+static GAMES: AtomicU64 = AtomicU64::new(0);
+
+/// Plays a Battlesnake HTTP server, e.g. `http://localhost:8000`.
+///
+/// Each game starts with a POST to `/start` and ends with one to `/end`, with a POST to
+/// `/move` every tick in between, all carrying Battlesnake's game state. Battlesnake puts
+/// the origin in the bottom-left corner, so `y` is flipped on the way out; its `up` still
+/// means the same as [`Direction::Up`]. Level walls are sent as hazards, and snakes
+/// always have full health. Failed or late requests keep the current direction.
+pub struct Battlesnake {
+    host: String,
+    path: String,
+    timeout: Duration,
+    game: Option<String>,
+    turn: u64,
+}
+
+impl Battlesnake {
+    pub fn new(url: &str, timeout: Duration) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidInput, format!("expected http://<host>:<port>[/path], got {}", url));
+        let rest = url.strip_prefix("http://").ok_or_else(invalid)?;
+        let (host, path) = rest.split_once('/').map_or((rest, ""), |(host, path)| (host, path));
+        if host.is_empty() {
+            return Err(invalid());
+        }
+
+        let host = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+        let path = match path.trim_end_matches('/') {
+            "" => String::new(),
+            path => format!("/{}", path),
+        };
+        Ok(Self { host, path, timeout, game: None, turn: 0 })
+    }
+
+    fn post(&self, endpoint: &str, body: &str) -> io::Result<String> {
+        let addr = self.host.to_socket_addrs()?.next().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no address"))?;
+        let mut stream = TcpStream::connect_timeout(&addr, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        write!(
+            stream,
+            "POST {}{} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            endpoint,
+            self.host,
+            body.len(),
+            body
+        )?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        parse_response(&String::from_utf8_lossy(&response))
+    }
+
+    fn request(&self, endpoint: &str, body: &str) -> Option<String> {
+        match self.post(endpoint, body) {
+            Ok(response) => Some(response),
+            Err(err) => {
+                eprintln!("battlesnake http://{}{}{}: {}", self.host, self.path, endpoint, err);
+                None
+            }
+        }
+    }
+
+    fn state(&self, view: &View) -> String {
+        let game = self.game.as_deref().unwrap_or_default();
+        let (width, height) = view.board();
+        let flip = |pos: Position| format!("{{\"x\":{},\"y\":{}}}", pos.x, height - 1 - pos.y);
+        let wrapped = Direction::ALL.iter().all(|&dir| view.boundary.edge(dir) == Edge::Wrap);
+        let ruleset = if wrapped { "wrapped" } else { "standard" };
+
+        let mut hazards = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let pos = Position::new(x, y);
+                if view.obstacles.contains(pos) {
+                    hazards.push(flip(pos));
+                }
+            }
+        }
+
+        let snake = |id: &str, snake: &Snake| {
+            let body: Vec<_> = snake.segments().map(|segment| flip(segment.0)).collect();
+            format!(
+                "{{\"id\":\"{id}\",\"name\":\"{id}\",\"health\":100,\"body\":[{}],\"latency\":\"0\",\"head\":{},\"length\":{},\"shout\":\"\"}}",
+                body.join(","),
+                flip(snake.head.0),
+                snake.length(),
+            )
+        };
+        let you = snake("you", view.snake);
+        let mut snakes = vec![you.clone()];
+        snakes.extend(view.others.iter().enumerate().map(|(i, other)| snake(&format!("snake-{}", i + 1), other)));
+
+        let mut json = String::new();
+        let _ = write!(
+            json,
+            "{{\"game\":{{\"id\":\"{}\",\"ruleset\":{{\"name\":\"{}\",\"version\":\"v1.0.0\"}},\"map\":\"standard\",\"timeout\":{},\"source\":\"custom\"}},\
+\"turn\":{},\"board\":{{\"height\":{},\"width\":{},\"food\":[{}],\"hazards\":[{}],\"snakes\":[{}]}},\"you\":{}}}",
+            game,
+            ruleset,
+            self.timeout.as_millis(),
+            self.turn,
+            height,
+            width,
+            flip(view.food.pos),
+            hazards.join(","),
+            snakes.join(","),
+            you
+        );
+        json
+    }
+}
+
+impl Controller for Battlesnake {
+    fn steer(&mut self, view: &View) -> Option<Direction> {
+        if self.game.is_none() {
+            self.game = Some(format!("snake-{}-{}", process::id(), GAMES.fetch_add(1, Ordering::Relaxed)));
+            self.turn = 0;
+            self.request("/start", &self.state(view));
+        }
+
+        let response = self.request("/move", &self.state(view));
+        self.turn += 1;
+        let name = string_field(&response?, "move")?;
+        match name.parse() {
+            Ok(dir) => Some(dir),
+            Err(()) => {
+                eprintln!("battlesnake http://{}{}: ignoring move {:?}", self.host, self.path, name);
+                None
+            }
+        }
+    }
+
+    fn end(&mut self, view: &View) {
+        if self.game.is_some() {
+            self.request("/end", &self.state(view));
+            self.game = None;
+        }
+    }
+}
+
+/// Returns the body of a successful HTTP/1.1 response, undoing chunked encoding.
+fn parse_response(response: &str) -> io::Result<String> {
+    let error = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let (head, body) = response.split_once("\r\n\r\n").ok_or_else(|| error("truncated response".to_string()))?;
+    let status = head.lines().next().unwrap_or_default();
+    if !status.split_whitespace().nth(1).is_some_and(|code| code.starts_with('2')) {
+        return Err(error(format!("unexpected response: {}", status)));
+    }
+
+    let chunked = head.lines().any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+    if !chunked {
+        return Ok(body.to_string());
+    }
+
+    let mut decoded = String::new();
+    let mut rest = body;
+    loop {
+        let (size, after) = rest.split_once("\r\n").ok_or_else(|| error("truncated chunk".to_string()))?;
+        let size = usize::from_str_radix(size.split(';').next().unwrap_or_default().trim(), 16).map_err(|_| error(format!("bad chunk size {:?}", size)))?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+        let chunk = after.get(..size).ok_or_else(|| error("truncated chunk".to_string()))?;
+        decoded.push_str(chunk);
+        rest = after[size..].trim_start_matches("\r\n");
+    }
+}
+
+/// Pulls the string value of `"field"` out of a flat JSON object.
+fn string_field(json: &str, field: &str) -> Option<String> {
+    let key = format!("\"{}\"", field);
+    let after = &json[json.find(&key)? + key.len()..];
+    let value = after.trim_start().strip_prefix(':')?.trim_start().strip_prefix('"')?;
+    Some(value[..value.find('"')?].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    use super::*;
+    use crate::World;
+
+    /// Answers one request per reply, sending each request's path and body back.
+    fn stub(replies: Vec<&'static str>) -> (String, mpsc::Receiver<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || {
+            for reply in replies {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let path = line.split_whitespace().nth(1).unwrap().to_string();
+
+                let mut length = 0;
+                loop {
+                    line.clear();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                sender.send((path, String::from_utf8(body).unwrap())).unwrap();
+
+                let mut stream = reader.into_inner();
+                write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}", reply.len(), reply).unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    #[test]
+    fn plays_a_game_against_a_server() {
+        let moves = ["up", "down", "left", "right"];
+        let mut replies = vec!["{}"];
+        replies.extend([r#"{"move":"up"}"#, r#"{"move": "down", "shout": "hi"}"#, r#"{"move":"left"}"#, r#"{"move":"right"}"#]);
+        replies.push("{}");
+        let (url, requests) = stub(replies);
+
+        let world = World::new(1);
+        let mut snake = Battlesnake::new(&url, Duration::from_secs(5)).unwrap();
+        for name in moves {
+            assert_eq!(snake.steer(&world.view()), name.parse().ok());
+        }
+        snake.end(&world.view());
+
+        let requests: Vec<_> = requests.iter().collect();
+        let paths: Vec<_> = requests.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(paths, ["/start", "/move", "/move", "/move", "/move", "/end"]);
+
+        let height = world.config().board.1;
+        let (head, food) = (world.snake().head.0, world.food().pos);
+        let body = &requests[1].1;
+        assert!(body.contains(&format!("\"head\":{{\"x\":{},\"y\":{}}}", head.x, height - 1 - head.y)));
+        assert!(body.contains(&format!("\"food\":[{{\"x\":{},\"y\":{}}}]", food.x, height - 1 - food.y)));
+        assert!(body.contains("\"turn\":0,"));
+        assert!(requests[4].1.contains("\"turn\":3,"));
+    }
+
+    #[test]
+    fn starts_a_new_game_after_the_end() {
+        let (url, requests) = stub(vec!["{}", r#"{"move":"up"}"#, "{}", "{}", r#"{"move":"up"}"#]);
+        let world = World::new(1);
+        let mut snake = Battlesnake::new(&url, Duration::from_secs(5)).unwrap();
+        snake.steer(&world.view());
+        snake.end(&world.view());
+        snake.end(&world.view());
+        snake.steer(&world.view());
+
+        let requests: Vec<_> = requests.iter().collect();
+        let paths: Vec<_> = requests.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(paths, ["/start", "/move", "/end", "/start", "/move"]);
+        assert_ne!(string_field(&requests[0].1, "id"), string_field(&requests[3].1, "id"));
+        assert!(requests[4].1.contains("\"turn\":0,"));
+    }
+
+    #[test]
+    fn decodes_chunked_responses() {
+        let response = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n7\r\n{\"move\"\r\n8;ext=1\r\n:\"left\"}\r\n0\r\n\r\n";
+        assert_eq!(parse_response(response).unwrap(), "{\"move\":\"left\"}");
+        assert!(parse_response("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n10\r\nshort").is_err());
+    }
+
+    #[test]
+    fn rejects_failed_responses() {
+        assert!(parse_response("HTTP/1.1 500 Internal Server Error\r\nContent-Length: 4\r\n\r\noops").is_err());
+        assert!(parse_response("HTTP/1.1 404 Not Found\r\n\r\n").is_err());
+        assert!(parse_response("HTTP/1.1 200 OK\r\nContent-Length: 2").is_err());
+        assert_eq!(parse_response("HTTP/1.1 204 No Content\r\n\r\n").unwrap(), "");
+    }
+}
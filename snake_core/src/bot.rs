@@ -0,0 +1,259 @@
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Battlesnake, Controller, Direction, Position, Snake, View};
+
+pub const DEFAULT_BOT_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Connects to a [`Battlesnake`] server when `target` is an `http://` URL and starts
+/// `target` as a [`Bot`] otherwise.
+pub fn open_bot(target: &str, timeout: Duration) -> io::Result<Box<dyn Controller + Send>> {
+    if target.starts_with("http://") {
+        Ok(Box::new(Battlesnake::new(target, timeout)?))
+    } else {
+        Ok(Box::new(Bot::spawn(target, timeout)?))
+    }
+}
+
+/// A snake steered by an external program.
+///
+/// Before every tick the bot is sent one line of JSON describing the board:
+///
+/// ```text
+/// {"tick":12,"width":40,"height":40,"snakes":[{"head":{"x":3,"y":4},"body":[{"x":2,"y":4}]}],"food":[{"x":9,"y":1,"kind":"normal"}]}
+/// ```
+///
+/// The first snake is the bot's own. It answers with a line holding `up`, `down`,
+/// `left` or `right`. A bot that answers late, answers nonsense, or has exited keeps
+/// going in its current direction, as does one that is still reading the previous
+/// state. Every state must get exactly one answer: late answers are thrown away once
+/// they arrive, so they are never taken as the move for a later tick.
+pub struct Bot {
+    name: String,
+    child: Child,
+    states: Option<SyncSender<String>>,
+    lines: Receiver<String>,
+    timeout: Duration,
+    /// States sent whose answer hasn't been read yet.
+    unanswered: usize,
+}
+
+impl Bot {
+    /// Starts `command`, a program followed by whitespace separated arguments.
+    pub fn spawn(command: &str, timeout: Duration) -> io::Result<Self> {
+        let mut parts = command.split_whitespace();
+        let program = parts.next().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty bot command"))?;
+        let mut child = Command::new(program).args(parts).stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
+
+        // Writes happen on their own thread so a bot that stops reading can't block the game.
+        let mut stdin = child.stdin.take().expect("bot stdin is piped");
+        let (states, pending) = mpsc::sync_channel::<String>(1);
+        thread::spawn(move || {
+            for state in pending {
+                if stdin.write_all(state.as_bytes()).and_then(|_| stdin.flush()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let stdout = child.stdout.take().expect("bot stdout is piped");
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self { name: command.to_string(), states: Some(states), child, lines, timeout, unanswered: 0 })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the bot can still be asked for moves.
+    pub fn is_running(&self) -> bool {
+        self.states.is_some()
+    }
+
+    fn disconnect(&mut self, reason: &str) {
+        if self.states.take().is_some() {
+            eprintln!("bot {}: {}, keeping its current direction", self.name, reason);
+        }
+    }
+}
+
+impl Controller for Bot {
+    fn steer(&mut self, view: &View) -> Option<Direction> {
+        let tick = view.tick;
+
+        // Every state gets exactly one answer, so the first `unanswered` lines to
+        // arrive belong to states whose deadline has already passed.
+        while self.unanswered > 0 && self.lines.try_recv().is_ok() {
+            self.unanswered -= 1;
+        }
+
+        match self.states.as_ref()?.try_send(state(view, tick)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                eprintln!("bot {}: still reading the last state on tick {}", self.name, tick);
+                return None;
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                self.disconnect("stopped reading its input");
+                return None;
+            }
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let line = match self.lines.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    eprintln!("bot {}: no move within {:?} on tick {}", self.name, self.timeout, tick);
+                    self.unanswered += 1;
+                    return None;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.disconnect("exited");
+                    return None;
+                }
+            };
+            if self.unanswered > 0 {
+                self.unanswered -= 1;
+                continue;
+            }
+
+            return match line.trim().parse() {
+                Ok(dir) => Some(dir),
+                Err(()) => {
+                    eprintln!("bot {}: ignoring move {:?} on tick {}", self.name, line.trim(), tick);
+                    None
+                }
+            };
+        }
+    }
+}
+
+impl Drop for Bot {
+    fn drop(&mut self) {
+        self.states = None;
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn point(json: &mut String, pos: Position) {
+    let _ = write!(json, "{{\"x\":{},\"y\":{}}}", pos.x, pos.y);
+}
+
+fn snake(json: &mut String, snake: &Snake) {
+    json.push_str("{\"head\":");
+    point(json, snake.head.0);
+    json.push_str(",\"body\":[");
+    for (i, segment) in snake.body.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        point(json, segment.0);
+    }
+    json.push_str("]}");
+}
+
+/// The board as one line of JSON, the viewing snake first.
+fn state(view: &View, tick: u64) -> String {
+    let (width, height) = view.board();
+    let mut json = format!("{{\"tick\":{},\"width\":{},\"height\":{},\"snakes\":[", tick, width, height);
+    for (i, other) in std::iter::once(view.snake).chain(view.others.iter().copied()).enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        snake(&mut json, other);
+    }
+    let food = view.food;
+    let _ = writeln!(json, "],\"food\":[{{\"x\":{},\"y\":{},\"kind\":\"{}\"}}]}}", food.pos.x, food.pos.y, food.kind);
+    json
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{fs, os::unix::fs::PermissionsExt, path::PathBuf};
+
+    use super::*;
+    use crate::World;
+
+    /// Writes `body` to an executable shell script named after the test.
+    fn script(name: &str, body: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("snake-bot-{}-{}.sh", name, std::process::id()));
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn spawn(name: &str, body: &str, timeout: Duration) -> Bot {
+        Bot::spawn(script(name, body).to_str().unwrap(), timeout).unwrap()
+    }
+
+    #[test]
+    fn answers_each_tick_with_the_world_tick() {
+        let log = std::env::temp_dir().join(format!("snake-bot-log-{}", std::process::id()));
+        let mut bot = spawn("echo", &format!("while read line; do echo \"$line\" >> {}; echo left; done", log.display()), Duration::from_secs(5));
+        let mut world = World::new(1);
+        world.step(None);
+        world.step(None);
+
+        assert_eq!(bot.steer(&world.view()), Some(Direction::Left));
+        world.step(None);
+        assert_eq!(bot.steer(&world.view()), Some(Direction::Left));
+
+        let sent = fs::read_to_string(&log).unwrap();
+        let ticks: Vec<_> = sent.lines().map(|line| line.split(',').next().unwrap()).collect();
+        assert_eq!(ticks, ["{\"tick\":2", "{\"tick\":3"]);
+        let _ = fs::remove_file(log);
+    }
+
+    #[test]
+    fn never_uses_a_late_answer_for_a_later_tick() {
+        let mut bot = spawn("slow", "while read line; do sleep 0.3; echo up; done", Duration::from_millis(50));
+        let mut world = World::new(1);
+        for _ in 0..8 {
+            assert_eq!(bot.steer(&world.view()), None, "tick {}", world.tick());
+            world.step(None);
+        }
+    }
+
+    #[test]
+    fn catches_up_after_a_slow_answer() {
+        let mut bot = spawn("catch-up", "read line; sleep 0.3; echo up; while read line; do echo down; done", Duration::from_millis(50));
+        let world = World::new(1);
+        assert_eq!(bot.steer(&world.view()), None);
+        thread::sleep(Duration::from_millis(400));
+        assert_eq!(bot.steer(&world.view()), Some(Direction::Down));
+        assert_eq!(bot.steer(&world.view()), Some(Direction::Down));
+    }
+
+    #[test]
+    fn keeps_going_when_the_bot_exits() {
+        let mut bot = spawn("exit", "exit 0", Duration::from_secs(5));
+        let world = World::new(1);
+        assert_eq!(bot.steer(&world.view()), None);
+        assert!(!bot.is_running());
+        assert_eq!(bot.steer(&world.view()), None);
+    }
+
+    #[test]
+    fn ignores_garbage() {
+        let mut bot = spawn("garbage", "while read line; do echo sideways; done", Duration::from_secs(5));
+        let world = World::new(1);
+        assert_eq!(bot.steer(&world.view()), None);
+        assert!(bot.is_running());
+    }
+}
@@ -0,0 +1,136 @@
+use std::{fmt, str::FromStr};
+
+use crate::{Direction, Position};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edge {
+    Wrap,
+    Wall,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Boundary {
+    pub top: Edge,
+    pub bottom: Edge,
+    pub left: Edge,
+    pub right: Edge,
+}
+
+impl Boundary {
+    pub fn wrap() -> Self {
+        Self { top: Edge::Wrap, bottom: Edge::Wrap, left: Edge::Wrap, right: Edge::Wrap }
+    }
+
+    pub fn walls() -> Self {
+        Self { top: Edge::Wall, bottom: Edge::Wall, left: Edge::Wall, right: Edge::Wall }
+    }
+
+    pub fn edge(&self, dir: Direction) -> Edge {
+        match dir {
+            Direction::Up => self.top,
+            Direction::Down => self.bottom,
+            Direction::Left => self.left,
+            Direction::Right => self.right,
+        }
+    }
+
+    pub fn advance(&self, pos: Position, dir: Direction, board: (i16, i16)) -> Option<Position> {
+        let crosses_edge = match dir {
+            Direction::Up => pos.y == 0,
+            Direction::Down => pos.y == board.1 - 1,
+            Direction::Left => pos.x == 0,
+            Direction::Right => pos.x == board.0 - 1,
+        };
+
+        if crosses_edge && self.edge(dir) == Edge::Wall {
+            return None;
+        }
+
+        Some(Position::new_from_move(pos, dir, board))
+    }
+}
+
+impl Default for Boundary {
+    fn default() -> Self {
+        Boundary::wrap()
+    }
+}
+
+impl fmt::Display for Boundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == Boundary::wrap() {
+            return f.write_str("wrap");
+        }
+        if *self == Boundary::walls() {
+            return f.write_str("walls");
+        }
+
+        for (edge, name) in [(self.top, 't'), (self.bottom, 'b'), (self.left, 'l'), (self.right, 'r')] {
+            if edge == Edge::Wall {
+                write!(f, "{}", name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Boundary {
+    type Err = String;
+
+    /// Accepts `wrap`, `walls`, or any combination of `t`, `b`, `l` and `r` naming the walled edges.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wrap" => return Ok(Boundary::wrap()),
+            "walls" => return Ok(Boundary::walls()),
+            "" => return Err("empty boundary".to_string()),
+            _ => {}
+        }
+
+        let mut boundary = Boundary::wrap();
+        for c in s.chars() {
+            let edge = match c {
+                't' => &mut boundary.top,
+                'b' => &mut boundary.bottom,
+                'l' => &mut boundary.left,
+                'r' => &mut boundary.right,
+                _ => return Err(format!("invalid boundary {:?}, expected wrap, walls or edges from \"tblr\"", s)),
+            };
+            *edge = Edge::Wall;
+        }
+        Ok(boundary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_combination() {
+        for walls in 0..16 {
+            let edge = |bit: u32| if walls & (1 << bit) != 0 { Edge::Wall } else { Edge::Wrap };
+            let boundary = Boundary { top: edge(0), bottom: edge(1), left: edge(2), right: edge(3) };
+            assert_eq!(boundary.to_string().parse::<Boundary>(), Ok(boundary), "{}", boundary);
+        }
+        assert_eq!(Boundary::walls().to_string(), "walls");
+        assert_eq!("tblr".parse(), Ok(Boundary::walls()));
+        assert_eq!("rt".parse::<Boundary>().unwrap().to_string(), "tr");
+    }
+
+    #[test]
+    fn rejects_unknown_edges() {
+        for s in ["", "x", "top", "walls,t", "Wrap"] {
+            assert!(s.parse::<Boundary>().is_err(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn stops_only_at_walled_edges() {
+        let boundary: Boundary = "l".parse().unwrap();
+        let board = (5, 4);
+        assert_eq!(boundary.advance(Position::new(0, 2), Direction::Left, board), None);
+        assert_eq!(boundary.advance(Position::new(4, 2), Direction::Right, board), Some(Position::new(0, 2)));
+        assert_eq!(boundary.advance(Position::new(1, 0), Direction::Up, board), Some(Position::new(1, 3)));
+        assert_eq!(boundary.advance(Position::new(1, 2), Direction::Left, board), Some(Position::new(0, 2)));
+    }
+}
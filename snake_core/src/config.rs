@@ -0,0 +1,132 @@
+use crate::{Boundary, FoodWeights, Level};
+
+pub const MIN_BOARD: i16 = 3;
+pub const DEFAULT_INPUT_DEPTH: usize = 3;
+pub const DEFAULT_BONUS_TICKS: u64 = 40;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpeedCurve {
+    pub min_rate: u32,
+    pub max_rate: u32,
+    pub step: u32,
+    pub food_per_level: u32,
+}
+
+impl SpeedCurve {
+    pub fn level(&self, eaten: u32) -> u32 {
+        1 + eaten / self.food_per_level
+    }
+
+    pub fn tick_rate(&self, level: u32) -> u32 {
+        self.min_rate.saturating_add(self.step.saturating_mul(level - 1)).min(self.max_rate)
+    }
+}
+
+impl Default for SpeedCurve {
+    fn default() -> Self {
+        Self { min_rate: 8, max_rate: 20, step: 1, food_per_level: 5 }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub board: (i16, i16),
+    pub boundary: Boundary,
+    pub input_depth: usize,
+    pub speed: SpeedCurve,
+    pub level: Option<Level>,
+    pub food: FoodWeights,
+    pub bonus_ticks: u64,
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.board.0 < MIN_BOARD || self.board.1 < MIN_BOARD {
+            return Err(format!(
+                "board must be at least {}x{}, got {}x{}",
+                MIN_BOARD, MIN_BOARD, self.board.0, self.board.1
+            ));
+        }
+        if self.input_depth == 0 {
+            return Err("input depth must be at least 1".to_string());
+        }
+        if let Some(level) = &self.level {
+            if level.size != self.board {
+                return Err(format!(
+                    "level {} is {}x{} but the board is {}x{}",
+                    level.name, level.size.0, level.size.1, self.board.0, self.board.1
+                ));
+            }
+            // The level only checks the tail cell as if every edge wrapped.
+            if self.boundary.advance(level.spawn, level.facing.inverse(), self.board).is_none() {
+                return Err(format!(
+                    "level {} spawns at {},{} with its tail past the wall to the {}",
+                    level.name,
+                    level.spawn.x + 1,
+                    level.spawn.y + 1,
+                    level.facing.inverse()
+                ));
+            }
+        }
+        match self.food.total() {
+            Some(0) => return Err("at least one food kind needs a weight".to_string()),
+            None => return Err(format!("food weights must add up to at most {}", u32::MAX)),
+            Some(_) => {}
+        }
+        if self.bonus_ticks == 0 {
+            return Err("bonus food must last at least one tick".to_string());
+        }
+        let speed = &self.speed;
+        if speed.min_rate == 0 || speed.max_rate < speed.min_rate {
+            return Err(format!("invalid speed range {}-{}", speed.min_rate, speed.max_rate));
+        }
+        if speed.food_per_level == 0 {
+            return Err("food per level must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            board: (40, 40),
+            boundary: Boundary::default(),
+            input_depth: DEFAULT_INPUT_DEPTH,
+            speed: SpeedCurve::default(),
+            level: None,
+            food: FoodWeights::default(),
+            bonus_ticks: DEFAULT_BONUS_TICKS,
+        }
+    }
+}
+
+/// Parses a board size written as `<width>x<height>`.
+pub fn parse_board(s: &str) -> Option<(i16, i16)> {
+    let (width, height) = s.split_once('x')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(rows: &[&str], boundary: &str) -> Config {
+        let level = Level::parse("edge", &rows.join("\n")).unwrap();
+        Config { board: level.size, boundary: boundary.parse().unwrap(), level: Some(level), ..Config::default() }
+    }
+
+    #[test]
+    fn rejects_spawns_with_the_tail_past_a_wall() {
+        let rows = [">....", ".....", ".....", ".....", "....."];
+        assert_eq!(config(&rows, "wrap").validate(), Ok(()));
+        assert_eq!(config(&rows, "t").validate(), Ok(()));
+        let err = config(&rows, "walls").validate().unwrap_err();
+        assert_eq!(err, "level edge spawns at 1,1 with its tail past the wall to the left");
+        assert!(config(&rows, "l").validate().is_err());
+
+        let rows = [".....", ".....", ".....", ".....", "..^.."];
+        assert_eq!(config(&rows, "l").validate(), Ok(()));
+        assert!(config(&rows, "b").validate().is_err());
+    }
+}
@@ -0,0 +1,442 @@
+use crate::{Config, Direction, Edge, Position, StepOutcome, World};
+
+/// Reward for each kind of event in a step, summed into the step's reward.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rewards {
+    pub food: f32,
+    pub death: f32,
+    /// Added on every step, usually a small negative number to discourage stalling.
+    pub step: f32,
+    /// Multiplies how many cells closer to the food the head moved this step.
+    pub distance: f32,
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Self { food: 1.0, death: -1.0, step: -0.01, distance: 0.0 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// Four `height` x `width` planes, one after the other: own body, own head, food
+    /// and obstacles, each cell 1.0 when set.
+    Grid,
+    /// A square of `2 * radius + 1` cells centred on the head and turned so the snake
+    /// faces up, as two planes: cells that would kill the snake, then food.
+    Window { radius: u16 },
+    /// [`FEATURES`] values: danger straight ahead, to the left and to the right; the
+    /// heading one-hot in [`Direction::ALL`] order; whether the food is up, down, left
+    /// and right of the head; the offset to the food over the board size; and the
+    /// fraction of the board the snake covers.
+    Features,
+}
+
+pub const FEATURES: usize = 14;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvConfig {
+    pub world: Config,
+    pub rewards: Rewards,
+    pub encoding: Encoding,
+    /// Steps without eating after which an episode is cut short, if any.
+    pub starve: Option<u64>,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        Self { world: Config::default(), rewards: Rewards::default(), encoding: Encoding::Grid, starve: None }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Info {
+    pub outcome: StepOutcome,
+    pub score: u32,
+    pub length: usize,
+    pub tick: u64,
+    /// The episode ended because the snake starved, not because it died or won.
+    pub truncated: bool,
+}
+
+pub type Observation = Vec<f32>;
+
+/// A gym-style training environment over a single player [`World`].
+///
+/// Actions are absolute directions; index them with [`Direction::ALL`]. Asking the snake
+/// to reverse keeps it going straight, as it would for a player.
+#[derive(Clone)]
+pub struct Env {
+    config: EnvConfig,
+    world: World,
+    last_meal: u64,
+    done: bool,
+}
+
+impl Env {
+    pub fn new(config: EnvConfig) -> Self {
+        let world = World::with_config(0, config.world.clone());
+        Self { config, world, last_meal: 0, done: false }
+    }
+
+    pub fn config(&self) -> &EnvConfig {
+        &self.config
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.restart(seed);
+        self.observation()
+    }
+
+    /// Starts a new episode without building an observation.
+    pub fn restart(&mut self, seed: u64) {
+        self.world.reset(seed);
+        self.last_meal = 0;
+        self.done = false;
+    }
+
+    pub fn step(&mut self, action: Direction) -> (Observation, f32, bool, Info) {
+        let (reward, done, info) = self.advance(action);
+        (self.observation(), reward, done, info)
+    }
+
+    /// Steps the world without building an observation.
+    pub fn advance(&mut self, action: Direction) -> (f32, bool, Info) {
+        let rewards = self.config.rewards;
+        let before = self.food_distance();
+        let outcome = if self.done { StepOutcome::Over } else { self.world.step(Some(action)) };
+
+        let mut reward = 0.0;
+        let mut truncated = false;
+        match outcome {
+            StepOutcome::Over => {}
+            StepOutcome::Died(_) => reward += rewards.step + rewards.death,
+            StepOutcome::Ate | StepOutcome::Won => {
+                reward += rewards.step + rewards.food;
+                self.last_meal = self.world.tick();
+            }
+            StepOutcome::Moved => {
+                reward += rewards.step + rewards.distance * (before - self.food_distance()) as f32;
+                truncated = self.config.starve.is_some_and(|starve| self.world.tick() - self.last_meal >= starve);
+            }
+        }
+        self.done = self.world.is_over() || truncated;
+
+        let info = Info {
+            outcome,
+            score: self.world.score(),
+            length: self.world.snake().length(),
+            tick: self.world.tick(),
+            truncated,
+        };
+        (reward, self.done, info)
+    }
+
+    pub fn observation_len(&self) -> usize {
+        let (width, height) = self.config.world.board;
+        match self.config.encoding {
+            Encoding::Grid => 4 * width as usize * height as usize,
+            Encoding::Window { radius } => 2 * (2 * radius as usize + 1).pow(2),
+            Encoding::Features => FEATURES,
+        }
+    }
+
+    pub fn observation(&self) -> Observation {
+        let mut observation = vec![0.0; self.observation_len()];
+        self.observe(&mut observation);
+        observation
+    }
+
+    /// Writes the current observation into `out`, which must be `observation_len()` long.
+    pub fn observe(&self, out: &mut [f32]) {
+        out.fill(0.0);
+        match self.config.encoding {
+            Encoding::Grid => self.observe_grid(out),
+            Encoding::Window { radius } => self.observe_window(out, radius),
+            Encoding::Features => self.observe_features(out),
+        }
+    }
+
+    fn observe_grid(&self, out: &mut [f32]) {
+        let (width, height) = self.config.world.board;
+        let plane = width as usize * height as usize;
+        let index = |pos: Position| pos.y as usize * width as usize + pos.x as usize;
+
+        let snake = self.world.snake();
+        for segment in snake.body.iter() {
+            out[index(segment.0)] = 1.0;
+        }
+        out[plane + index(snake.head.0)] = 1.0;
+        out[2 * plane + index(self.world.food().pos)] = 1.0;
+        for y in 0..height {
+            for x in 0..width {
+                let pos = Position::new(x, y);
+                if self.world.obstacles().contains(pos) {
+                    out[3 * plane + index(pos)] = 1.0;
+                }
+            }
+        }
+    }
+
+    fn observe_window(&self, out: &mut [f32], radius: u16) {
+        let radius = radius as i32;
+        let side = 2 * radius + 1;
+        let plane = (side * side) as usize;
+        let snake = self.world.snake();
+        let head = snake.head.0;
+
+        for ly in -radius..=radius {
+            for lx in -radius..=radius {
+                let (dx, dy) = match snake.dir {
+                    Direction::Up => (lx, ly),
+                    Direction::Right => (-ly, lx),
+                    Direction::Down => (-lx, -ly),
+                    Direction::Left => (ly, -lx),
+                };
+                let index = ((ly + radius) * side + lx + radius) as usize;
+                match self.resolve(head.x as i32 + dx, head.y as i32 + dy) {
+                    Some(pos) if pos == self.world.food().pos => out[plane + index] = 1.0,
+                    Some(pos) if !self.is_deadly(pos) => {}
+                    _ => out[index] = 1.0,
+                }
+            }
+        }
+    }
+
+    fn observe_features(&self, out: &mut [f32]) {
+        let (width, height) = self.config.world.board;
+        let snake = self.world.snake();
+        let head = snake.head.0;
+        let dir = snake.dir;
+
+        let left = match dir {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        };
+        for (i, turn) in [dir, left, left.inverse()].into_iter().enumerate() {
+            let next = self.config.world.boundary.advance(head, turn, (width, height));
+            let safe = next.is_some_and(|pos| !self.is_deadly(pos));
+            out[i] = if safe { 0.0 } else { 1.0 };
+        }
+
+        out[3 + Direction::ALL.iter().position(|&d| d == dir).unwrap_or_default()] = 1.0;
+
+        let (dx, dy) = self.food_offset();
+        out[7] = (dy < 0) as u8 as f32;
+        out[8] = (dy > 0) as u8 as f32;
+        out[9] = (dx < 0) as u8 as f32;
+        out[10] = (dx > 0) as u8 as f32;
+        out[11] = dx as f32 / width as f32;
+        out[12] = dy as f32 / height as f32;
+        out[13] = snake.length() as f32 / (width as f32 * height as f32);
+    }
+
+    /// The board cell at `(x, y)`, wrapping across edges that wrap.
+    fn resolve(&self, x: i32, y: i32) -> Option<Position> {
+        let (width, height) = self.config.world.board;
+        let (width, height) = (width as i32, height as i32);
+        let boundary = &self.config.world.boundary;
+        let crosses = [(x < 0, Direction::Left), (x >= width, Direction::Right), (y < 0, Direction::Up), (y >= height, Direction::Down)];
+        if crosses.iter().any(|&(crossed, dir)| crossed && boundary.edge(dir) == Edge::Wall) {
+            return None;
+        }
+        Some(Position::new(x.rem_euclid(width) as i16, y.rem_euclid(height) as i16))
+    }
+
+    fn is_deadly(&self, pos: Position) -> bool {
+        self.world.snake().occupies(pos) || self.world.obstacles().contains(pos)
+    }
+
+    /// Shortest offset from the head to the food, going across edges that wrap.
+    fn food_offset(&self) -> (i32, i32) {
+        let (width, height) = self.config.world.board;
+        let boundary = &self.config.world.boundary;
+        let head = self.world.snake().head.0;
+        let food = self.world.food().pos;
+
+        let axis = |delta: i32, size: i32, negative: Direction, positive: Direction| {
+            let across = if delta > 0 { delta - size } else { delta + size };
+            let wraps = if across < 0 { boundary.edge(negative) } else { boundary.edge(positive) } == Edge::Wrap;
+            if wraps && across.abs() < delta.abs() {
+                across
+            } else {
+                delta
+            }
+        };
+        (
+            axis(food.x as i32 - head.x as i32, width as i32, Direction::Left, Direction::Right),
+            axis(food.y as i32 - head.y as i32, height as i32, Direction::Up, Direction::Down),
+        )
+    }
+
+    fn food_distance(&self) -> i32 {
+        let (dx, dy) = self.food_offset();
+        dx.abs() + dy.abs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Boundary, Food, FoodKind, Level};
+
+    /// A 10x10 game whose snake starts at (2, 5) facing right, with the food at `food`.
+    fn env(encoding: Encoding, boundary: Boundary, food: (i16, i16)) -> Env {
+        let world = Config { board: (10, 10), boundary, ..Config::default() };
+        let rewards = Rewards { food: 10.0, death: -5.0, step: -0.25, distance: 0.5 };
+        let mut env = Env::new(EnvConfig { world, rewards, encoding, starve: None });
+        env.reset(1);
+        env.world.place_food(Food::new(food.into(), FoodKind::Normal));
+        env
+    }
+
+    #[test]
+    fn resets_to_the_same_episode() {
+        let mut env = env(Encoding::Features, Boundary::walls(), (8, 8));
+        let first = env.reset(4);
+        assert_eq!(first.len(), env.observation_len());
+        while !env.step(Direction::Up).2 {}
+
+        assert_eq!(env.reset(4), first);
+        assert_eq!(env.world().tick(), 0);
+        let (_, _, done, info) = env.step(Direction::Up);
+        assert!(!done);
+        assert_eq!(info.tick, 1);
+    }
+
+    #[test]
+    fn rewards_each_event() {
+        let mut env = env(Encoding::Features, Boundary::walls(), (5, 5));
+        assert_eq!(env.step(Direction::Right).1, -0.25 + 0.5);
+        assert_eq!(env.step(Direction::Down).1, -0.25 - 0.5);
+        assert_eq!(env.step(Direction::Right).1, -0.25 + 0.5);
+        assert_eq!(env.step(Direction::Up).1, -0.25 + 0.5);
+
+        let (_, reward, done, info) = env.step(Direction::Right);
+        assert_eq!((reward, done, info.outcome), (-0.25 + 10.0, false, StepOutcome::Ate));
+        assert_eq!((info.score, info.length), (1, 3));
+
+        env.world.place_food(Food::new((9, 9).into(), FoodKind::Normal));
+        let (_, reward, done, info) = loop {
+            let step = env.step(Direction::Up);
+            if step.2 {
+                break step;
+            }
+        };
+        assert_eq!((reward, done, info.truncated), (-0.25 - 5.0, true, false));
+        assert!(matches!(info.outcome, StepOutcome::Died(_)));
+
+        let (_, reward, done, info) = env.step(Direction::Up);
+        assert_eq!((reward, done, info.outcome), (0.0, true, StepOutcome::Over));
+    }
+
+    #[test]
+    fn cuts_starving_episodes_short() {
+        let mut starving = env(Encoding::Features, Boundary::wrap(), (2, 0));
+        starving.config.starve = Some(3);
+        starving.step(Direction::Up);
+        starving.step(Direction::Up);
+        let (_, _, done, info) = starving.step(Direction::Up);
+        assert!(done && info.truncated);
+        assert_eq!(info.outcome, StepOutcome::Moved);
+
+        let mut fed = env(Encoding::Features, Boundary::wrap(), (2, 3));
+        fed.config.starve = Some(3);
+        fed.step(Direction::Up);
+        fed.step(Direction::Up);
+        let (_, _, done, info) = fed.step(Direction::Up);
+        assert!(!done && !info.truncated);
+    }
+
+    #[test]
+    fn grid_has_a_plane_per_layer() {
+        let level = Level::parse("test", ".....\n.>...\n....#\n.....").unwrap();
+        let world = Config { board: level.size, level: Some(level), ..Config::default() };
+        let mut env = Env::new(EnvConfig { world, ..EnvConfig::default() });
+        env.reset(1);
+        env.world.place_food(Food::new((3, 3).into(), FoodKind::Normal));
+
+        let observation = env.observation();
+        assert_eq!(observation.len(), 4 * 20);
+        let set: Vec<_> = observation.iter().enumerate().filter(|&(_, &value)| value == 1.0).map(|(i, _)| i).collect();
+        assert_eq!(set, [5, 20 + 6, 40 + 18, 60 + 14]);
+    }
+
+    /// Cells set in each plane of a radius 1 window, numbered in reading order. The head
+    /// itself, cell 4, is always deadly.
+    fn window(env: &Env) -> (Vec<usize>, Vec<usize>) {
+        let observation = env.observation();
+        let set = |plane: &[f32]| plane.iter().enumerate().filter(|&(_, &value)| value == 1.0).map(|(i, _)| i).collect();
+        (set(&observation[..9]), set(&observation[9..]))
+    }
+
+    #[test]
+    fn window_turns_with_the_snake() {
+        let radius = Encoding::Window { radius: 1 };
+
+        // Facing right: the food ahead is straight up the window, the tail straight down.
+        let env_right = env(radius, Boundary::wrap(), (3, 5));
+        assert_eq!(window(&env_right), (vec![4, 7], vec![1]));
+
+        // Facing up with the food to the left, then down with it to the right: the
+        // food is on the snake's left both times.
+        let mut env_up = env(radius, Boundary::wrap(), (1, 4));
+        env_up.step(Direction::Up);
+        assert_eq!(window(&env_up), (vec![4, 7], vec![3]));
+
+        let mut env_down = env(radius, Boundary::wrap(), (3, 6));
+        env_down.step(Direction::Down);
+        assert_eq!(window(&env_down), (vec![4, 7], vec![3]));
+
+        // Facing left, the cell above is on the snake's right.
+        let mut env_left = env(radius, Boundary::wrap(), (1, 3));
+        env_left.step(Direction::Up);
+        env_left.step(Direction::Left);
+        assert_eq!(window(&env_left), (vec![4, 7], vec![5]));
+    }
+
+    #[test]
+    fn window_marks_walls_as_deadly() {
+        let mut env = env(Encoding::Window { radius: 1 }, Boundary::walls(), (9, 9));
+        for _ in 0..5 {
+            env.step(Direction::Up);
+        }
+        assert_eq!(env.world().snake().head.0, Position::new(2, 0));
+        assert_eq!(window(&env), (vec![0, 1, 2, 4, 7], vec![]));
+
+        let env = Env::new(EnvConfig { encoding: Encoding::Window { radius: 0 }, ..EnvConfig::default() });
+        assert_eq!(env.observation_len(), 2);
+    }
+
+    #[test]
+    fn food_offset_wraps_only_across_open_edges() {
+        let env_wrap = env(Encoding::Features, Boundary::wrap(), (9, 5));
+        assert_eq!(env_wrap.food_offset(), (-3, 0));
+        let features = env_wrap.observation();
+        assert_eq!(&features[7..13], &[0.0, 0.0, 1.0, 0.0, -0.3, 0.0]);
+
+        assert_eq!(env(Encoding::Features, "l".parse().unwrap(), (9, 5)).food_offset(), (7, 0));
+        assert_eq!(env(Encoding::Features, "r".parse().unwrap(), (9, 5)).food_offset(), (-3, 0));
+        assert_eq!(env(Encoding::Features, Boundary::wrap(), (2, 9)).food_offset(), (0, 4));
+        assert_eq!(env(Encoding::Features, Boundary::wrap(), (2, 0)).food_offset(), (0, -5));
+        assert_eq!(env(Encoding::Features, "t".parse().unwrap(), (2, 1)).food_offset(), (0, -4));
+    }
+
+    #[test]
+    fn features_report_danger_and_heading() {
+        let mut env = env(Encoding::Features, Boundary::walls(), (9, 9));
+        for _ in 0..7 {
+            env.step(Direction::Right);
+        }
+        assert_eq!(env.world().snake().head.0, Position::new(9, 5));
+        let features = env.observation();
+        assert_eq!(&features[..7], &[1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(features[13], 2.0 / 100.0);
+    }
+}
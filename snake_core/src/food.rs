@@ -0,0 +1,176 @@
+use std::{fmt, str::FromStr};
+
+use oorandom::Rand32;
+
+use crate::Position;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FoodKind {
+    Normal,
+    Golden,
+    Shrinking,
+    SpeedUp,
+    SlowDown,
+    Bonus,
+}
+
+impl FoodKind {
+    pub const ALL: [FoodKind; 6] = [
+        FoodKind::Normal,
+        FoodKind::Golden,
+        FoodKind::Shrinking,
+        FoodKind::SpeedUp,
+        FoodKind::SlowDown,
+        FoodKind::Bonus,
+    ];
+
+    pub fn points(&self) -> u32 {
+        match self {
+            FoodKind::Golden => 5,
+            FoodKind::Bonus => 3,
+            _ => 1,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            FoodKind::Normal => "normal",
+            FoodKind::Golden => "golden",
+            FoodKind::Shrinking => "shrinking",
+            FoodKind::SpeedUp => "speed_up",
+            FoodKind::SlowDown => "slow_down",
+            FoodKind::Bonus => "bonus",
+        }
+    }
+}
+
+impl fmt::Display for FoodKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for FoodKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FoodKind::ALL.into_iter().find(|kind| kind.name() == s).ok_or(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Food {
+    pub pos: Position,
+    pub kind: FoodKind,
+    pub expires_at: Option<u64>,
+}
+
+impl Food {
+    pub fn new(pos: Position, kind: FoodKind) -> Self {
+        Self { pos, kind, expires_at: None }
+    }
+}
+
+/// Relative spawn weights for each food kind, in `FoodKind::ALL` order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FoodWeights(pub [u32; 6]);
+
+impl FoodWeights {
+    pub fn classic() -> Self {
+        FoodWeights([1, 0, 0, 0, 0, 0])
+    }
+
+    pub fn varied() -> Self {
+        FoodWeights([20, 3, 3, 2, 2, 4])
+    }
+
+    pub fn weight(&self, kind: FoodKind) -> u32 {
+        self.0[kind as usize]
+    }
+
+    /// Sum of all weights, or `None` if it doesn't fit in a `u32`.
+    pub fn total(&self) -> Option<u32> {
+        self.0.iter().try_fold(0u32, |total, &weight| total.checked_add(weight))
+    }
+
+    /// Picks a kind by weight. Only draws from `rng` when more than one kind can spawn,
+    /// so single-kind modes keep the same food sequence for a given seed.
+    pub fn pick(&self, rng: &mut Rand32) -> FoodKind {
+        let mut kinds = FoodKind::ALL.into_iter().filter(|&kind| self.weight(kind) > 0);
+        let first = kinds.next().unwrap_or(FoodKind::Normal);
+        if kinds.next().is_none() {
+            return first;
+        }
+
+        let mut roll = rng.rand_range(0..self.total().unwrap_or(u32::MAX));
+        for kind in FoodKind::ALL {
+            let weight = self.weight(kind);
+            if roll < weight {
+                return kind;
+            }
+            roll -= weight;
+        }
+        first
+    }
+}
+
+impl Default for FoodWeights {
+    fn default() -> Self {
+        FoodWeights::classic()
+    }
+}
+
+impl fmt::Display for FoodWeights {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == FoodWeights::classic() {
+            return f.write_str("classic");
+        }
+        if *self == FoodWeights::varied() {
+            return f.write_str("varied");
+        }
+
+        let weights: Vec<_> = FoodKind::ALL.iter().map(|kind| format!("{}={}", kind, self.weight(*kind))).collect();
+        f.write_str(&weights.join(","))
+    }
+}
+
+impl FromStr for FoodWeights {
+    type Err = String;
+
+    /// Accepts `classic`, `varied`, or comma separated `<kind>=<weight>` pairs with
+    /// unlisted kinds weighted zero.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "classic" => return Ok(FoodWeights::classic()),
+            "varied" => return Ok(FoodWeights::varied()),
+            _ => {}
+        }
+
+        let mut weights = FoodWeights([0; 6]);
+        for pair in s.split(',') {
+            let (kind, weight) = pair.split_once('=').ok_or_else(|| format!("expected <kind>=<weight>, got {:?}", pair))?;
+            let kind: FoodKind = kind.trim().parse().map_err(|_| format!("unknown food kind {:?}", kind))?;
+            weights.0[kind as usize] = weight.trim().parse().map_err(|_| format!("invalid weight {:?}", weight))?;
+        }
+        match weights.total() {
+            Some(0) => Err("at least one food kind needs a weight".to_string()),
+            None => Err(format!("food weights must add up to at most {}", u32::MAX)),
+            Some(_) => Ok(weights),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_weights_that_overflow() {
+        assert!("normal=4294967295,golden=1".parse::<FoodWeights>().is_err());
+        assert!("normal=0".parse::<FoodWeights>().is_err());
+
+        let weights: FoodWeights = "normal=4294967294,golden=1".parse().unwrap();
+        assert_eq!(weights.total(), Some(u32::MAX));
+        assert_eq!(FoodWeights([u32::MAX, 1, 0, 0, 0, 0]).total(), None);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::Position;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OccupancyGrid {
+    width: i16,
+    height: i16,
+    bits: Vec<u64>,
+    occupied: usize,
+}
+
+impl OccupancyGrid {
+    pub fn new(width: i16, height: i16) -> Self {
+        let cells = width as usize * height as usize;
+        Self { width, height, bits: vec![0; cells.div_ceil(64)], occupied: 0 }
+    }
+
+    pub fn width(&self) -> i16 {
+        self.width
+    }
+
+    pub fn height(&self) -> i16 {
+        self.height
+    }
+
+    pub fn cells(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    pub fn occupied(&self) -> usize {
+        self.occupied
+    }
+
+    pub fn free(&self) -> usize {
+        self.cells() - self.occupied
+    }
+
+    fn index(&self, pos: Position) -> usize {
+        pos.y as usize * self.width as usize + pos.x as usize
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        let index = self.index(pos);
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn insert(&mut self, pos: Position) {
+        let index = self.index(pos);
+        let word = &mut self.bits[index / 64];
+        if *word & (1 << (index % 64)) == 0 {
+            *word |= 1 << (index % 64);
+            self.occupied += 1;
+        }
+    }
+
+    pub fn remove(&mut self, pos: Position) {
+        let index = self.index(pos);
+        let word = &mut self.bits[index / 64];
+        if *word & (1 << (index % 64)) != 0 {
+            *word &= !(1 << (index % 64));
+            self.occupied -= 1;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|word| *word = 0);
+        self.occupied = 0;
+    }
+
+    /// Marks every cell occupied in `other`, which must have the same size.
+    pub fn union(&mut self, other: &OccupancyGrid) {
+        self.bits.iter_mut().zip(&other.bits).for_each(|(a, b)| *a |= b);
+        self.occupied = self.bits.iter().map(|word| word.count_ones() as usize).sum();
+    }
+
+    pub fn nth_free(&self, nth: usize) -> Option<Position> {
+        self.nth_free_words(nth, self.bits.iter().copied())
+    }
+
+    /// Number of cells free in both this grid and `other`, which must have the same size.
+    pub fn free_with(&self, other: &OccupancyGrid) -> usize {
+        let taken: usize = self.bits.iter().zip(&other.bits).map(|(a, b)| (a | b).count_ones() as usize).sum();
+        self.cells() - taken
+    }
+
+    pub fn nth_free_with(&self, other: &OccupancyGrid, nth: usize) -> Option<Position> {
+        self.nth_free_words(nth, self.bits.iter().zip(&other.bits).map(|(a, b)| a | b))
+    }
+
+    fn nth_free_words(&self, mut nth: usize, words: impl Iterator<Item = u64>) -> Option<Position> {
+        let cells = self.cells();
+        for (i, word) in words.enumerate() {
+            let valid = if (i + 1) * 64 <= cells { u64::MAX } else { (1 << (cells % 64)) - 1 };
+            let mut free = !word & valid;
+            let count = free.count_ones() as usize;
+            if nth >= count {
+                nth -= count;
+                continue;
+            }
+            for _ in 0..nth {
+                free &= free - 1;
+            }
+            let index = i * 64 + free.trailing_zeros() as usize;
+            return Some(Position::new((index % self.width as usize) as i16, (index / self.width as usize) as i16));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 13x10 is 130 cells: two full words and a partial third.
+    fn grid() -> OccupancyGrid {
+        OccupancyGrid::new(13, 10)
+    }
+
+    fn at(index: usize) -> Position {
+        Position::new((index % 13) as i16, (index / 13) as i16)
+    }
+
+    #[test]
+    fn finds_free_cells_across_word_boundaries() {
+        let mut grid = grid();
+        for index in 0..130 {
+            assert_eq!(grid.nth_free(index), Some(at(index)));
+        }
+        assert_eq!(grid.nth_free(130), None);
+
+        (0..130).filter(|&index| ![63, 64, 127, 128, 129].contains(&index)).for_each(|index| grid.insert(at(index)));
+        assert_eq!(grid.free(), 5);
+        let free: Vec<_> = (0..6).map(|nth| grid.nth_free(nth)).collect();
+        assert_eq!(free, [Some(at(63)), Some(at(64)), Some(at(127)), Some(at(128)), Some(at(129)), None]);
+    }
+
+    #[test]
+    fn counts_each_cell_once() {
+        let mut grid = grid();
+        grid.insert(at(64));
+        grid.insert(at(64));
+        grid.remove(at(5));
+        assert_eq!((grid.occupied(), grid.free()), (1, 129));
+        assert!(grid.contains(at(64)) && !grid.contains(at(63)) && !grid.contains(at(65)));
+
+        grid.remove(at(64));
+        grid.remove(at(64));
+        assert_eq!(grid.occupied(), 0);
+    }
+
+    #[test]
+    fn combines_grids() {
+        let mut snake = grid();
+        let mut walls = grid();
+        (0..64).for_each(|index| snake.insert(at(index)));
+        (64..129).for_each(|index| walls.insert(at(index)));
+        walls.insert(at(0));
+
+        assert_eq!(snake.free_with(&walls), 1);
+        assert_eq!(snake.nth_free_with(&walls, 0), Some(at(129)));
+        assert_eq!(snake.nth_free_with(&walls, 1), None);
+
+        snake.union(&walls);
+        assert_eq!((snake.occupied(), snake.nth_free(0)), (129, Some(at(129))));
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{Autopilot, Controller, Direction, Position, View};
+
+/// A closed path visiting every cell of the board exactly once, moving only between
+/// orthogonally adjacent cells without wrapping. One exists whenever a side is even.
+#[derive(Clone, Debug)]
+pub struct Cycle {
+    board: (i16, i16),
+    cells: Vec<Position>,
+    order: Vec<u32>,
+}
+
+impl Cycle {
+    /// Snakes up and down the board from the second column (or row) onwards and
+    /// returns along the first.
+    pub fn new(board: (i16, i16)) -> Option<Self> {
+        let (width, height) = board;
+        if width < 2 || height < 2 {
+            return None;
+        }
+
+        let transpose = height % 2 != 0;
+        let (columns, rows) = if transpose { (height, width) } else { (width, height) };
+        if rows % 2 != 0 {
+            return None;
+        }
+
+        let mut cells = Vec::with_capacity(width as usize * height as usize);
+        for row in 0..rows {
+            let xs: Box<dyn Iterator<Item = i16>> = if row % 2 == 0 { Box::new(1..columns) } else { Box::new((1..columns).rev()) };
+            cells.extend(xs.map(|x| (x, row)));
+        }
+        cells.extend((0..rows).rev().map(|row| (0, row)));
+
+        let cells: Vec<_> = cells.into_iter().map(|(x, y)| if transpose { Position::new(y, x) } else { Position::new(x, y) }).collect();
+        let mut cycle = Self { board, cells, order: Vec::new() };
+        cycle.index();
+        Some(cycle)
+    }
+
+    fn index(&mut self) {
+        self.order = vec![0; self.cells.len()];
+        for (i, &pos) in self.cells.iter().enumerate() {
+            let cell = pos.y as usize * self.board.0 as usize + pos.x as usize;
+            self.order[cell] = i as u32;
+        }
+    }
+
+    pub fn board(&self) -> (i16, i16) {
+        self.board
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Position of `pos` along the cycle.
+    pub fn order(&self, pos: Position) -> u32 {
+        self.order[pos.y as usize * self.board.0 as usize + pos.x as usize]
+    }
+
+    pub fn next(&self, pos: Position) -> Position {
+        self.cells[(self.order(pos) as usize + 1) % self.cells.len()]
+    }
+
+    /// Steps needed to walk the cycle from `from` to `to`.
+    pub fn distance(&self, from: Position, to: Position) -> u32 {
+        let len = self.cells.len() as u32;
+        (self.order(to) + len - self.order(from)) % len
+    }
+
+    pub fn reverse(&mut self) {
+        self.cells.reverse();
+        self.index();
+    }
+}
+
+/// Follows a Hamiltonian cycle. A snake that only ever follows the cycle from the start
+/// of a single player game cannot die, and fills the board.
+///
+/// It never cuts across the cycle towards the food: a shortcut leaves skipped cells
+/// empty behind the head, and if every new food then lands ahead of it the head runs
+/// into the tail before those cells are reached, so no shortcut is safe with random food.
+///
+/// Boards with obstacles or without an even side fall back to the [`Autopilot`].
+#[derive(Clone, Debug, Default)]
+pub struct Hamiltonian {
+    cycle: Option<Cycle>,
+}
+
+impl Controller for Hamiltonian {
+    fn steer(&mut self, view: &View) -> Option<Direction> {
+        let board = view.board();
+        if self.cycle.as_ref().map(Cycle::board) != Some(board) {
+            self.cycle = Cycle::new(board);
+        }
+        let cycle = match &mut self.cycle {
+            Some(cycle) if view.obstacles.occupied() == 0 => cycle,
+            _ => return Autopilot.steer(view),
+        };
+
+        let snake = view.snake;
+        let head = snake.head.0;
+        if snake.body.front().is_some_and(|neck| cycle.next(head) == neck.0) {
+            cycle.reverse();
+        }
+
+        let target = cycle.next(head);
+        Direction::ALL.into_iter().find(|&dir| view.boundary.advance(head, dir, board) == Some(target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, StepOutcome, World};
+
+    #[test]
+    fn builds_closed_cycles() {
+        for board in [(2, 2), (4, 3), (3, 4), (6, 6), (7, 10), (40, 40)] {
+            let cycle = Cycle::new(board).unwrap();
+            let cells = board.0 as usize * board.1 as usize;
+            assert_eq!(cycle.len(), cells);
+
+            let start = Position::new(0, 0);
+            let mut seen = vec![false; cells];
+            let mut pos = start;
+            for _ in 0..cells {
+                let index = pos.y as usize * board.0 as usize + pos.x as usize;
+                assert!(!seen[index], "{:?} visits {:?} twice", board, pos);
+                seen[index] = true;
+
+                let next = cycle.next(pos);
+                assert_eq!((next.x - pos.x).abs() + (next.y - pos.y).abs(), 1, "{:?} jumps from {:?} to {:?}", board, pos, next);
+                assert_eq!(cycle.distance(pos, next), 1);
+                pos = next;
+            }
+            assert_eq!(pos, start);
+        }
+    }
+
+    #[test]
+    fn needs_an_even_side() {
+        assert!(Cycle::new((3, 3)).is_none());
+        assert!(Cycle::new((5, 7)).is_none());
+        assert!(Cycle::new((1, 4)).is_none());
+    }
+
+    #[test]
+    fn fills_small_boards() {
+        for board in [(4, 3), (3, 4), (4, 4), (6, 5), (6, 6)] {
+            let config = Config { board, ..Config::default() };
+            for seed in 0..300 {
+                let mut world = World::with_config(seed, config.clone());
+                let mut pilot = Hamiltonian::default();
+                let outcome = loop {
+                    let dir = pilot.steer(&world.view());
+                    match world.step(dir) {
+                        StepOutcome::Moved | StepOutcome::Ate => {}
+                        outcome => break outcome,
+                    }
+                };
+                assert_eq!(outcome, StepOutcome::Won, "board {:?}, seed {}", board, seed);
+            }
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::{fmt, fs, io, path::Path};
+
+use crate::{Direction, Position, MIN_BOARD};
+
+/// A board layout read from an ASCII level file.
+///
+/// Each line is one row of the board: `#` is a wall, `.` is floor, `F` is a fixed
+/// food location and `S` marks the spawn cell facing right. `^`, `v`, `<` and `>`
+/// mark the spawn cell facing up, down, left or right. Fixed food is placed in
+/// reading order before food falls back to random free cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Level {
+    pub name: String,
+    pub size: (i16, i16),
+    pub walls: Vec<Position>,
+    pub spawn: Position,
+    pub facing: Direction,
+    pub food: Vec<Position>,
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(io::Error),
+    Parse { line: usize, column: usize, message: String },
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(err) => write!(f, "{}", err),
+            LevelError::Parse { line, column, message } => write!(f, "{}:{}: {}", line, column, message),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl From<io::Error> for LevelError {
+    fn from(err: io::Error) -> Self {
+        LevelError::Io(err)
+    }
+}
+
+impl Level {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LevelError> {
+        let path = path.as_ref();
+        let name = path.file_stem().map_or_else(|| "level".to_string(), |stem| stem.to_string_lossy().into_owned());
+        Level::parse(&name, &fs::read_to_string(path)?)
+    }
+
+    pub fn parse(name: &str, text: &str) -> Result<Self, LevelError> {
+        let error = |line: usize, column: usize, message: String| LevelError::Parse { line, column, message };
+
+        let rows: Vec<&str> = text.lines().map(|line| line.trim_end()).collect();
+        let height = rows.iter().rposition(|row| !row.is_empty()).map_or(0, |last| last + 1);
+        let width = rows.first().map_or(0, |row| row.chars().count());
+
+        if width < MIN_BOARD as usize || height < MIN_BOARD as usize {
+            return Err(error(1, 1, format!("level must be at least {}x{}, got {}x{}", MIN_BOARD, MIN_BOARD, width, height)));
+        }
+        if width > i16::MAX as usize || height > i16::MAX as usize {
+            return Err(error(1, 1, format!("level is too large: {}x{}", width, height)));
+        }
+
+        let mut walls = Vec::new();
+        let mut food = Vec::new();
+        let mut spawn = None;
+
+        for (y, row) in rows[..height].iter().enumerate() {
+            let columns = row.chars().count();
+            if columns != width {
+                return Err(error(y + 1, columns.min(width) + 1, format!("expected {} columns, found {}", width, columns)));
+            }
+
+            for (x, c) in row.chars().enumerate() {
+                let pos = Position::new(x as i16, y as i16);
+                let facing = match c {
+                    '#' => {
+                        walls.push(pos);
+                        continue;
+                    }
+                    '.' => continue,
+                    'F' => {
+                        food.push(pos);
+                        continue;
+                    }
+                    'S' | '>' => Direction::Right,
+                    '<' => Direction::Left,
+                    '^' => Direction::Up,
+                    'v' => Direction::Down,
+                    _ => return Err(error(y + 1, x + 1, format!("unexpected character {:?}", c))),
+                };
+                if spawn.is_some() {
+                    return Err(error(y + 1, x + 1, "more than one spawn".to_string()));
+                }
+                spawn = Some((pos, facing));
+            }
+        }
+
+        let (spawn, facing) = spawn.ok_or_else(|| error(1, 1, "level has no spawn".to_string()))?;
+        let size = (width as i16, height as i16);
+        let tail = Position::new_from_move(spawn, facing.inverse(), size);
+        if walls.contains(&tail) || food.contains(&tail) {
+            return Err(error(
+                spawn.y as usize + 1,
+                spawn.x as usize + 1,
+                "the cell behind the spawn must be floor".to_string(),
+            ));
+        }
+        if width * height - walls.len() <= 2 {
+            return Err(error(spawn.y as usize + 1, spawn.x as usize + 1, "level has no free cell for food".to_string()));
+        }
+
+        Ok(Self { name: name.to_string(), size, walls, spawn, facing, food })
+    }
+
+    pub fn rows(&self) -> Vec<String> {
+        let (width, height) = (self.size.0 as usize, self.size.1 as usize);
+        let mut grid = vec![vec!['.'; width]; height];
+        for wall in &self.walls {
+            grid[wall.y as usize][wall.x as usize] = '#';
+        }
+        for food in &self.food {
+            grid[food.y as usize][food.x as usize] = 'F';
+        }
+        grid[self.spawn.y as usize][self.spawn.x as usize] = match self.facing {
+            Direction::Up => '^',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Right => '>',
+        };
+        grid.into_iter().map(|row| row.into_iter().collect()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_at(text: &str) -> (usize, usize) {
+        match Level::parse("test", text) {
+            Err(LevelError::Parse { line, column, .. }) => (line, column),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_a_level() {
+        let level = Level::parse("test", "#..F\n.v..\n....\n").unwrap();
+        assert_eq!(level.size, (4, 3));
+        assert_eq!(level.walls, [Position::new(0, 0)]);
+        assert_eq!((level.spawn, level.facing), (Position::new(1, 1), Direction::Down));
+        assert_eq!(level.food, [Position::new(3, 0)]);
+        assert_eq!(level.rows(), ["#..F", ".v..", "...."]);
+    }
+
+    #[test]
+    fn reports_line_and_column() {
+        assert_eq!(error_at("...\n.>.\n..x\n"), (3, 3));
+        assert_eq!(error_at("....\n.>.\n....\n"), (2, 4));
+        assert_eq!(error_at("...\n.>.\n.....\n"), (3, 4));
+        assert_eq!(error_at(".>.\n...\n..<\n"), (3, 3));
+        assert_eq!(error_at("...\n...\n...\n"), (1, 1));
+        assert_eq!(error_at("...\n#>.\n...\n"), (2, 2));
+        assert_eq!(error_at("...\n.>.\n"), (1, 1));
+    }
+
+    #[test]
+    fn rejects_levels_without_room_for_food() {
+        let err = Level::parse("full", "###\n.>#\n###").unwrap_err();
+        assert!(matches!(err, LevelError::Parse { line: 2, column: 2, .. }), "{}", err);
+        assert!(Level::parse("tight", "###\n.>.\n###").is_ok());
+    }
+}
@@ -0,0 +1,33 @@
+mod ai;
+mod battlesnake;
+mod boundary;
+mod bot;
+mod config;
+mod env;
+mod food;
+mod grid;
+mod hamiltonian;
+mod level;
+mod position;
+mod replay;
+mod snake;
+mod vec_env;
+mod versus;
+mod world;
+
+pub use ai::{Ai, Autopilot, Controller, View};
+pub use battlesnake::Battlesnake;
+pub use boundary::{Boundary, Edge};
+pub use bot::{open_bot, Bot, DEFAULT_BOT_TIMEOUT};
+pub use config::{parse_board, Config, SpeedCurve, DEFAULT_BONUS_TICKS, DEFAULT_INPUT_DEPTH, MIN_BOARD};
+pub use env::{Encoding, Env, EnvConfig, Info, Observation, Rewards, FEATURES};
+pub use food::{Food, FoodKind, FoodWeights};
+pub use grid::OccupancyGrid;
+pub use hamiltonian::{Cycle, Hamiltonian};
+pub use level::{Level, LevelError};
+pub use position::{Direction, Position};
+pub use replay::{Playback, Replay, ReplayError, ReplayInput, REPLAY_VERSION};
+pub use snake::{Segment, Snake, Touched};
+pub use vec_env::{Batch, VecEnv};
+pub use versus::{Match, Player, RoundOutcome, Versus};
+pub use world::{StepOutcome, World};
@@ -0,0 +1,85 @@
+use std::{fmt, str::FromStr};
+
+use oorandom::Rand32;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub x: i16,
+    pub y: i16,
+}
+
+impl Position {
+    pub fn new(x: i16, y: i16) -> Self {
+        Self { x, y }
+    }
+
+    pub fn random(rng: &mut Rand32, max_x: i16, max_y: i16) -> Self {
+        (
+            rng.rand_range(0..max_x as u32) as i16,
+            rng.rand_range(0..max_y as u32) as i16,
+        )
+            .into()
+    }
+
+    pub fn new_from_move(pos: Position, dir: Direction, board: (i16, i16)) -> Self {
+        match dir {
+            Direction::Up => Position::new(pos.x, (pos.y - 1).rem_euclid(board.1)),
+            Direction::Down => Position::new(pos.x, (pos.y + 1).rem_euclid(board.1)),
+            Direction::Left => Position::new((pos.x - 1).rem_euclid(board.0), pos.y),
+            Direction::Right => Position::new((pos.x + 1).rem_euclid(board.0), pos.y),
+        }
+    }
+}
+
+impl From<(i16, i16)> for Position {
+    fn from(pos: (i16, i16)) -> Self {
+        Position { x: pos.0, y: pos.1 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    pub fn inverse(&self) -> Self {
+        match *self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(Direction::Up),
+            "down" => Ok(Direction::Down),
+            "left" => Ok(Direction::Left),
+            "right" => Ok(Direction::Right),
+            _ => Err(()),
+        }
+    }
+}
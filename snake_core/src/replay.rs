@@ -0,0 +1,313 @@
+use std::{fmt, fs, io, path::Path};
+
+use crate::{parse_board, Config, Direction, Level, SpeedCurve, StepOutcome, World};
+
+const MAGIC: &str = "snake-replay";
+/// Bumped whenever the same seed and inputs could play out differently. Replays from
+/// other versions are rejected rather than played back wrong.
+pub const REPLAY_VERSION: u32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReplayInput {
+    pub tick: u64,
+    pub dir: Direction,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Replay {
+    pub seed: u64,
+    pub config: Config,
+    pub inputs: Vec<ReplayInput>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Version(u32),
+    Parse { line: usize, message: String },
+    Config(String),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "{}", err),
+            ReplayError::Version(version) => write!(f, "unsupported replay version {}", version),
+            ReplayError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            ReplayError::Config(message) => write!(f, "invalid settings: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(err: io::Error) -> Self {
+        ReplayError::Io(err)
+    }
+}
+
+impl Replay {
+    pub fn new(seed: u64, config: Config) -> Self {
+        Self { seed, config, inputs: Vec::new() }
+    }
+
+    pub fn world(&self) -> World {
+        World::with_config(self.seed, self.config.clone())
+    }
+
+    pub fn record(&mut self, tick: u64, dir: Direction) {
+        self.inputs.push(ReplayInput { tick, dir });
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", MAGIC, REPLAY_VERSION)?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "board {}x{}", self.config.board.0, self.config.board.1)?;
+        writeln!(f, "boundary {}", self.config.boundary)?;
+        writeln!(f, "input_depth {}", self.config.input_depth)?;
+        let speed = &self.config.speed;
+        writeln!(f, "speed {} {} {} {}", speed.min_rate, speed.max_rate, speed.step, speed.food_per_level)?;
+        writeln!(f, "food {}", self.config.food)?;
+        writeln!(f, "bonus_ticks {}", self.config.bonus_ticks)?;
+        if let Some(level) = &self.config.level {
+            writeln!(f, "level {}", level.name)?;
+            for row in level.rows() {
+                writeln!(f, "row {}", row)?;
+            }
+        }
+        for input in &self.inputs {
+            writeln!(f, "{} {}", input.tick, input.dir)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Replay {
+    type Err = ReplayError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_error = |line: usize, message: &str| ReplayError::Parse { line, message: message.to_string() };
+
+        let mut lines = s.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+
+        let version = match lines.next() {
+            Some((_, line)) => match line.split_once(' ') {
+                Some((MAGIC, version)) => version.parse().map_err(|_| parse_error(1, "invalid version"))?,
+                _ => return Err(parse_error(1, "not a replay file")),
+            },
+            None => return Err(parse_error(1, "empty file")),
+        };
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::Version(version));
+        }
+
+        let seed = match lines.next() {
+            Some((n, line)) => match line.split_once(' ') {
+                Some(("seed", seed)) => seed.parse().map_err(|_| parse_error(n, "invalid seed"))?,
+                _ => return Err(parse_error(n, "expected seed")),
+            },
+            None => return Err(parse_error(2, "missing seed")),
+        };
+
+        let mut replay = Replay::new(seed, Config::default());
+        let mut level: Option<(usize, String, Vec<&str>)> = None;
+        for (n, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            let (key, value) = line.split_once(' ').ok_or_else(|| parse_error(n, "expected <tick> <direction>"))?;
+            if !key.starts_with(|c: char| c.is_ascii_digit()) {
+                if !replay.inputs.is_empty() {
+                    return Err(parse_error(n, "settings must precede inputs"));
+                }
+                match (key, &mut level) {
+                    ("level", _) => level = Some((n, value.to_string(), Vec::new())),
+                    ("row", Some((_, _, rows))) => rows.push(value),
+                    ("row", None) => return Err(parse_error(n, "row before level")),
+                    _ => apply_setting(&mut replay.config, key, value).map_err(|message| parse_error(n, &message))?,
+                }
+                continue;
+            }
+
+            if replay.inputs.is_empty() {
+                replay.config = finish_settings(replay.config, level.take())?;
+            }
+
+            let (tick, dir) = (key, value);
+            let tick = tick.parse().map_err(|_| parse_error(n, "invalid tick"))?;
+            let dir = dir.parse().map_err(|_| parse_error(n, "invalid direction"))?;
+            if replay.inputs.last().is_some_and(|last| last.tick > tick) {
+                return Err(parse_error(n, "ticks out of order"));
+            }
+            replay.record(tick, dir);
+        }
+
+        if replay.inputs.is_empty() {
+            replay.config = finish_settings(replay.config, level)?;
+        }
+        Ok(replay)
+    }
+}
+
+fn finish_settings(mut config: Config, level: Option<(usize, String, Vec<&str>)>) -> Result<Config, ReplayError> {
+    if let Some((line, name, rows)) = level {
+        let level = Level::parse(&name, &rows.join("\n"))
+            .map_err(|err| ReplayError::Parse { line, message: format!("invalid level: {}", err) })?;
+        config.level = Some(level);
+    }
+    config.validate().map_err(ReplayError::Config)?;
+    Ok(config)
+}
+
+fn apply_setting(config: &mut Config, key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "board" => {
+            config.board = parse_board(value).ok_or_else(|| format!("invalid board size {:?}", value))?;
+        }
+        "boundary" => config.boundary = value.parse()?,
+        "input_depth" => {
+            config.input_depth = value.parse().map_err(|_| format!("invalid input depth {:?}", value))?;
+        }
+        "speed" => {
+            let values = value
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<Vec<u32>, _>>()
+                .map_err(|_| format!("invalid speed curve {:?}", value))?;
+            let [min_rate, max_rate, step, food_per_level] = values[..] else {
+                return Err(format!("expected <min> <max> <step> <food per level>, got {:?}", value));
+            };
+            config.speed = SpeedCurve { min_rate, max_rate, step, food_per_level };
+        }
+        "food" => config.food = value.parse()?,
+        "bonus_ticks" => config.bonus_ticks = value.parse().map_err(|_| format!("invalid bonus ticks {:?}", value))?,
+        _ => return Err(format!("unknown setting {:?}", key)),
+    }
+    Ok(())
+}
+
+pub struct Playback {
+    replay: Replay,
+    next: usize,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, next: 0 }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.replay.seed
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next == self.replay.inputs.len()
+    }
+
+    pub fn step(&mut self, world: &mut World) -> StepOutcome {
+        while let Some(input) = self.replay.inputs.get(self.next) {
+            if input.tick > world.tick() {
+                break;
+            }
+            world.turn(input.dir);
+            self.next += 1;
+        }
+        world.step(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Autopilot, Controller, FoodWeights};
+
+    fn config() -> Config {
+        let mut rows = ["............"; 10];
+        rows[2] = "..####......";
+        rows[5] = "...>....F...";
+        let level = Level::parse("arena", &rows.join("\n")).unwrap();
+        Config {
+            board: level.size,
+            boundary: "tl".parse().unwrap(),
+            input_depth: 2,
+            speed: SpeedCurve { min_rate: 6, max_rate: 12, step: 2, food_per_level: 3 },
+            level: Some(level),
+            food: FoodWeights::varied(),
+            bonus_ticks: 7,
+        }
+    }
+
+    #[test]
+    fn round_trips_through_text() {
+        let mut replay = Replay::new(42, config());
+        replay.record(0, Direction::Up);
+        replay.record(0, Direction::Left);
+        replay.record(9, Direction::Down);
+
+        let text = replay.to_string();
+        assert_eq!(text.parse::<Replay>().unwrap(), replay);
+        assert_eq!(Replay::new(7, Config::default()).to_string().parse::<Replay>().unwrap(), Replay::new(7, Config::default()));
+    }
+
+    #[test]
+    fn playback_matches_the_recorded_game() {
+        let mut world = World::with_config(3, config());
+        let mut replay = Replay::new(3, config());
+        let mut pilot = Autopilot;
+        while !world.is_over() && world.tick() < 1_000 {
+            if let Some(dir) = pilot.steer(&world.view()) {
+                let tick = world.tick();
+                if world.turn(dir) {
+                    replay.record(tick, dir);
+                }
+            }
+            world.step(None);
+        }
+
+        let replay: Replay = replay.to_string().parse().unwrap();
+        let mut played = replay.world();
+        let mut playback = Playback::new(replay);
+        while played.tick() < world.tick() {
+            playback.step(&mut played);
+        }
+        assert!(playback.is_finished());
+        assert_eq!(played.snake().head.0, world.snake().head.0);
+        assert_eq!(played.food(), world.food());
+        assert_eq!((played.score(), played.is_over()), (world.score(), world.is_over()));
+    }
+
+    #[test]
+    fn reports_the_line_of_bad_input() {
+        let line = |text: &str| match text.parse::<Replay>() {
+            Err(ReplayError::Parse { line, .. }) => line,
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        let header = format!("{} {}\nseed 1\n", MAGIC, REPLAY_VERSION);
+        assert_eq!(line("not a replay"), 1);
+        assert_eq!(line(&format!("{} {}\nseeds 1\n", MAGIC, REPLAY_VERSION)), 2);
+        assert_eq!(line(&format!("{}board 12x\n", header)), 3);
+        assert_eq!(line(&format!("{}0 up\n5 left\n3 down\n", header)), 5);
+        assert_eq!(line(&format!("{}0 up\nboard 12x10\n", header)), 4);
+        assert_eq!(line(&format!("{}row ...\n", header)), 3);
+        assert!(matches!(format!("{}board 12x10\nlevel x\nrow ...\nrow .>.\nrow ...\n", header).parse::<Replay>(), Err(ReplayError::Config(_))));
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        for version in [1, REPLAY_VERSION - 1, REPLAY_VERSION + 1] {
+            let text = format!("{} {}\nseed 1\n0 up\n", MAGIC, version);
+            assert!(matches!(text.parse::<Replay>(), Err(ReplayError::Version(v)) if v == version));
+        }
+        let current = format!("{} {}\nseed 1\n0 up\n", MAGIC, REPLAY_VERSION);
+        assert!(current.parse::<Replay>().is_ok());
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+
+use crate::{Boundary, Direction, Food, OccupancyGrid, Position};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Segment(pub Position);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Touched {
+    Body,
+    Food,
+    Wall,
+    Obstacle,
+    /// Ran into another snake's head or body.
+    Snake,
+}
+
+#[derive(Clone, Debug)]
+pub struct Snake {
+    pub head: Segment,
+    pub body: VecDeque<Segment>,
+    pub dir: Direction,
+    occupied: OccupancyGrid,
+    queue: VecDeque<Direction>,
+    input_depth: usize,
+    pub touched: Option<Touched>,
+}
+
+impl Snake {
+    pub fn new(pos: Position, dir: Direction, board: (i16, i16), input_depth: usize) -> Self {
+        let mut body = VecDeque::new();
+        let mut occupied = OccupancyGrid::new(board.0, board.1);
+
+        let tail = Position::new_from_move(pos, dir.inverse(), board);
+        body.push_back(Segment(tail));
+        occupied.insert(tail);
+        occupied.insert(pos);
+
+        Self {
+            head: Segment(pos),
+            body,
+            occupied,
+            dir,
+            queue: VecDeque::with_capacity(input_depth),
+            input_depth,
+            touched: None,
+        }
+    }
+
+    /// Puts the snake back to its starting length at `pos`, keeping its allocations.
+    pub fn reset(&mut self, pos: Position, dir: Direction) {
+        self.body.clear();
+        self.occupied.clear();
+        self.queue.clear();
+
+        let board = (self.occupied.width(), self.occupied.height());
+        let tail = Position::new_from_move(pos, dir.inverse(), board);
+        self.body.push_back(Segment(tail));
+        self.occupied.insert(tail);
+        self.occupied.insert(pos);
+
+        self.head = Segment(pos);
+        self.dir = dir;
+        self.touched = None;
+    }
+
+    pub fn length(&self) -> usize {
+        1 + self.body.len()
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = &Segment> {
+        std::iter::once(&self.head).chain(self.body.iter())
+    }
+
+    pub fn occupies(&self, pos: Position) -> bool {
+        self.occupied.contains(pos)
+    }
+
+    pub fn occupancy(&self) -> &OccupancyGrid {
+        &self.occupied
+    }
+
+    /// Drops up to `segments` segments from the tail, never shrinking below two segments.
+    pub fn shrink(&mut self, segments: usize) {
+        for _ in 0..segments {
+            if self.body.len() <= 1 {
+                break;
+            }
+            if let Some(tail) = self.body.pop_back() {
+                self.occupied.remove(tail.0);
+            }
+        }
+    }
+
+    pub fn queued(&self) -> impl Iterator<Item = &Direction> {
+        self.queue.iter()
+    }
+
+    /// Queues a turn to be taken on a later tick. Turns that repeat or reverse the
+    /// direction the snake will be heading in at that point are dropped, as are
+    /// turns beyond the queue depth.
+    pub fn turn(&mut self, dir: Direction) -> bool {
+        let heading = self.queue.back().copied().unwrap_or(self.dir);
+        if dir == heading || dir == heading.inverse() || self.queue.len() >= self.input_depth {
+            return false;
+        }
+        self.queue.push_back(dir);
+        true
+    }
+
+    fn ate_food(&self, food: &Food) -> bool {
+        self.head.0 == food.pos
+    }
+
+    fn eats_body(&self) -> bool {
+        self.occupied.contains(self.head.0)
+    }
+
+    pub fn update(&mut self, food: &Food, boundary: &Boundary, obstacles: &OccupancyGrid) {
+        if let Some(dir) = self.queue.pop_front() {
+            self.dir = dir;
+        }
+
+        let board = (self.occupied.width(), self.occupied.height());
+        let new_head_pos = match boundary.advance(self.head.0, self.dir, board) {
+            Some(pos) => pos,
+            None => {
+                self.touched = Some(Touched::Wall);
+                return;
+            }
+        };
+
+        if obstacles.contains(new_head_pos) {
+            self.touched = Some(Touched::Obstacle);
+            return;
+        }
+
+        let new_head = Segment(new_head_pos);
+
+        self.body.push_front(self.head);
+
+        self.head = new_head;
+
+        let collided = self.eats_body();
+        self.occupied.insert(self.head.0);
+
+        if collided {
+            self.touched = Some(Touched::Body);
+        } else if self.ate_food(food) {
+            self.touched = Some(Touched::Food);
+        } else {
+            self.touched = None;
+        }
+
+        if self.touched.is_none() {
+            if let Some(tail) = self.body.pop_back() {
+                self.occupied.remove(tail.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FoodKind;
+
+    fn snake(input_depth: usize) -> Snake {
+        Snake::new(Position::new(5, 5), Direction::Right, (10, 10), input_depth)
+    }
+
+    fn step(snake: &mut Snake) {
+        let food = Food::new(Position::new(0, 0), FoodKind::Normal);
+        snake.update(&food, &Boundary::wrap(), &OccupancyGrid::new(10, 10));
+    }
+
+    #[test]
+    fn drops_reversals_of_the_last_queued_turn() {
+        let mut snake = snake(3);
+        assert!(!snake.turn(Direction::Left));
+        assert!(snake.turn(Direction::Up));
+        assert!(!snake.turn(Direction::Down));
+        assert!(snake.turn(Direction::Left));
+        assert!(!snake.turn(Direction::Right));
+        assert_eq!(snake.queued().copied().collect::<Vec<_>>(), [Direction::Up, Direction::Left]);
+
+        // A quick up-then-left can't fold the snake back onto itself.
+        step(&mut snake);
+        step(&mut snake);
+        assert_eq!(snake.touched, None);
+        assert_eq!(snake.head.0, Position::new(4, 4));
+    }
+
+    #[test]
+    fn drops_repeated_turns() {
+        let mut snake = snake(3);
+        assert!(!snake.turn(Direction::Right));
+        assert!(snake.turn(Direction::Up));
+        assert!(!snake.turn(Direction::Up));
+        assert_eq!(snake.queued().count(), 1);
+    }
+
+    #[test]
+    fn stops_queueing_at_the_depth_limit() {
+        let mut snake = snake(2);
+        assert!(snake.turn(Direction::Up));
+        assert!(snake.turn(Direction::Right));
+        assert!(!snake.turn(Direction::Down));
+        assert_eq!(snake.queued().count(), 2);
+
+        step(&mut snake);
+        assert!(snake.turn(Direction::Down));
+        assert_eq!(snake.queued().count(), 2);
+    }
+
+    #[test]
+    fn takes_one_queued_turn_per_tick() {
+        let mut snake = snake(3);
+        snake.turn(Direction::Up);
+        snake.turn(Direction::Left);
+        snake.turn(Direction::Down);
+
+        let mut path = Vec::new();
+        for _ in 0..4 {
+            step(&mut snake);
+            path.push((snake.dir, snake.head.0));
+        }
+        assert_eq!(
+            path,
+            [
+                (Direction::Up, Position::new(5, 4)),
+                (Direction::Left, Position::new(4, 4)),
+                (Direction::Down, Position::new(4, 5)),
+                (Direction::Down, Position::new(4, 6)),
+            ]
+        );
+    }
+}
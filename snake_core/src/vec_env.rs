@@ -0,0 +1,154 @@
+use crate::{Direction, Env, EnvConfig, Info, StepOutcome};
+
+/// The results of stepping every game in a [`VecEnv`], indexed by game.
+pub struct Batch<'a> {
+    /// One observation per game, each `observation_len()` values long, back to back.
+    pub observations: &'a [f32],
+    pub rewards: &'a [f32],
+    pub dones: &'a [bool],
+    pub infos: &'a [Info],
+}
+
+/// Many independent [`Env`]s stepped together.
+///
+/// Games that finish are reset straight away with the next unused seed, so the
+/// observation returned for a done game is the first of its next episode while its
+/// reward and info still describe the final step. Stepping reuses the same buffers
+/// and each game's own allocations, so it only allocates when a snake grows longer
+/// than any before it in the same game.
+pub struct VecEnv {
+    envs: Vec<Env>,
+    next_seed: u64,
+    observation_len: usize,
+    observations: Vec<f32>,
+    rewards: Vec<f32>,
+    dones: Vec<bool>,
+    infos: Vec<Info>,
+}
+
+impl VecEnv {
+    /// Builds `games` environments seeded `seed`, `seed + 1` and so on.
+    pub fn new(config: EnvConfig, games: usize, seed: u64) -> Self {
+        let env = Env::new(config);
+        let observation_len = env.observation_len();
+        let info = Info { outcome: StepOutcome::Moved, score: 0, length: 0, tick: 0, truncated: false };
+
+        let mut vec_env = Self {
+            envs: vec![env; games],
+            next_seed: seed,
+            observation_len,
+            observations: vec![0.0; games * observation_len],
+            rewards: vec![0.0; games],
+            dones: vec![false; games],
+            infos: vec![info; games],
+        };
+        vec_env.reset(seed);
+        vec_env
+    }
+
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+
+    pub fn observation_len(&self) -> usize {
+        self.observation_len
+    }
+
+    pub fn envs(&self) -> &[Env] {
+        &self.envs
+    }
+
+    /// Restarts every game, seeding them `seed`, `seed + 1` and so on, and returns their
+    /// observations.
+    pub fn reset(&mut self, seed: u64) -> &[f32] {
+        self.next_seed = seed;
+        for (env, observation) in self.envs.iter_mut().zip(self.observations.chunks_exact_mut(self.observation_len)) {
+            env.restart(self.next_seed);
+            env.observe(observation);
+            self.next_seed += 1;
+        }
+        &self.observations
+    }
+
+    /// Steps every game with the action at the same index.
+    pub fn step(&mut self, actions: &[Direction]) -> Batch<'_> {
+        assert_eq!(actions.len(), self.envs.len(), "expected one action per game");
+
+        let games = self.envs.iter_mut().zip(self.observations.chunks_exact_mut(self.observation_len));
+        for (i, ((env, observation), &action)) in games.zip(actions).enumerate() {
+            let (reward, done, info) = env.advance(action);
+            if done {
+                env.restart(self.next_seed);
+                self.next_seed += 1;
+            }
+            env.observe(observation);
+            self.rewards[i] = reward;
+            self.dones[i] = done;
+            self.infos[i] = info;
+        }
+
+        Batch { observations: &self.observations, rewards: &self.rewards, dones: &self.dones, infos: &self.infos }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Boundary, Config, Encoding, Rewards};
+
+    fn config() -> EnvConfig {
+        let world = Config { board: (5, 5), boundary: Boundary::walls(), ..Config::default() };
+        EnvConfig { world, encoding: Encoding::Features, ..EnvConfig::default() }
+    }
+
+    #[test]
+    fn lays_out_buffers_per_game() {
+        let actions = [Direction::Up, Direction::Right, Direction::Down];
+        let mut vec_env = VecEnv::new(config(), 3, 10);
+        let len = vec_env.observation_len();
+        let mut envs: Vec<_> = (0..3).map(|_| Env::new(config())).collect();
+        for (i, env) in envs.iter_mut().enumerate() {
+            assert_eq!(&vec_env.reset(10)[i * len..(i + 1) * len], &env.reset(10 + i as u64)[..]);
+        }
+
+        let batch = vec_env.step(&actions);
+        assert_eq!(batch.observations.len(), 3 * len);
+        for (i, env) in envs.iter_mut().enumerate() {
+            let (observation, reward, done, info) = env.step(actions[i]);
+            assert_eq!(&batch.observations[i * len..(i + 1) * len], &observation[..], "game {}", i);
+            assert_eq!((batch.rewards[i], batch.dones[i], batch.infos[i]), (reward, done, info), "game {}", i);
+        }
+    }
+
+    #[test]
+    fn resets_finished_games_with_the_next_seed() {
+        let mut vec_env = VecEnv::new(config(), 2, 10);
+        let len = vec_env.observation_len();
+        let actions = [Direction::Up, Direction::Right];
+        vec_env.step(&actions);
+        vec_env.step(&actions);
+        let batch = vec_env.step(&actions);
+
+        // The snake heading up hits the wall on its third step, the other doesn't yet.
+        assert_eq!(batch.dones, [true, false]);
+        let rewards = Rewards::default();
+        assert_eq!(batch.rewards[0], rewards.step + rewards.death);
+        assert!(matches!(batch.infos[0].outcome, StepOutcome::Died(_)));
+        assert_eq!(batch.infos[0].tick, 3);
+        let mut fresh = Env::new(config());
+        assert_eq!(&batch.observations[..len], &fresh.reset(12)[..]);
+
+        assert_eq!(vec_env.envs()[0].world().seed(), 12);
+        assert_eq!(vec_env.envs()[0].world().tick(), 0);
+        assert_eq!(vec_env.envs()[1].world().tick(), 3);
+
+        // The other game takes the seed after that once it finishes too.
+        let batch = vec_env.step(&actions);
+        assert_eq!(batch.dones, [false, true]);
+        assert_eq!(vec_env.envs()[1].world().seed(), 13);
+    }
+}
@@ -0,0 +1,483 @@
+use oorandom::Rand32;
+
+use crate::{
+    world::{new_food, SHRINK_SEGMENTS, SPEED_EFFECT_DELTA, SPEED_EFFECT_TICKS},
+    Config, Direction, Food, FoodKind, OccupancyGrid, Position, Snake, Touched, View,
+};
+
+#[derive(Clone, Debug)]
+pub struct Player {
+    pub snake: Snake,
+    pub score: u32,
+    pub alive: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundOutcome {
+    Winner(usize),
+    Draw,
+}
+
+/// One round of several snakes sharing a board and its food.
+///
+/// A snake dies when it hits a wall, an obstacle, itself or another snake's body.
+/// When two heads meet the shorter snake dies, or both when they are the same length.
+/// The round ends once fewer than two snakes are alive, or when the board fills up,
+/// in which case the highest score among the survivors wins.
+#[derive(Clone)]
+pub struct Versus {
+    seed: u64,
+    config: Config,
+    rng: Rand32,
+    players: Vec<Player>,
+    food: Food,
+    obstacles: OccupancyGrid,
+    tick: u64,
+    eaten: u32,
+    tick_rate: u32,
+    speed_effect: Option<(i32, u64)>,
+    outcome: Option<RoundOutcome>,
+}
+
+impl Versus {
+    /// Player `i` spawns on its own row, even players on the left facing right and
+    /// odd players on the right facing left, except that the first player takes the
+    /// level's spawn when there is one. Snakes that would start on a wall or another
+    /// snake move to the nearest free cells instead. Fixed food is ignored.
+    pub fn new(seed: u64, config: Config, players: usize) -> Result<Self, String> {
+        let mut rng = Rand32::new(seed);
+
+        let board = config.board;
+        let mut obstacles = OccupancyGrid::new(board.0, board.1);
+        if let Some(level) = &config.level {
+            level.walls.iter().for_each(|&wall| obstacles.insert(wall));
+        }
+
+        let mut taken = obstacles.clone();
+        let mut spawned = Vec::with_capacity(players);
+        for i in 0..players {
+            let y = (board.1 as usize * (i + 1) / (players + 1)) as i16;
+            let (preferred, facing) = match &config.level {
+                Some(level) if i == 0 => (level.spawn, level.facing),
+                _ if i % 2 == 0 => (Position::new(board.0 / 4, y), Direction::Right),
+                _ => (Position::new(board.0 - 1 - board.0 / 4, y), Direction::Left),
+            };
+            let pos = spawn(&config, &taken, preferred, facing).ok_or_else(|| format!("no room to spawn player {}", i + 1))?;
+            let snake = Snake::new(pos, facing, board, config.input_depth);
+            taken.union(snake.occupancy());
+            spawned.push(Player { snake, score: 0, alive: true });
+        }
+        let players = spawned;
+
+        let pos = free_cell(&mut rng, &players, &obstacles).ok_or("no room for food")?;
+        let food = new_food(&mut rng, &config, pos, 0);
+        let tick_rate = config.speed.tick_rate(1);
+
+        Ok(Self {
+            seed,
+            config,
+            rng,
+            players,
+            food,
+            obstacles,
+            tick: 0,
+            eaten: 0,
+            tick_rate,
+            speed_effect: None,
+            outcome: None,
+        })
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    pub fn food(&self) -> &Food {
+        &self.food
+    }
+
+    pub fn obstacles(&self) -> &OccupancyGrid {
+        &self.obstacles
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn tick_rate(&self) -> u32 {
+        self.tick_rate
+    }
+
+    pub fn outcome(&self) -> Option<RoundOutcome> {
+        self.outcome
+    }
+
+    /// What `player` sees of the board, with every other living snake as an opponent.
+    pub fn view(&self, player: usize) -> View<'_> {
+        View {
+            boundary: &self.config.boundary,
+            obstacles: &self.obstacles,
+            snake: &self.players[player].snake,
+            others: self.players.iter().enumerate().filter(|&(i, other)| i != player && other.alive).map(|(_, other)| &other.snake).collect(),
+            food: &self.food,
+            tick: self.tick,
+        }
+    }
+
+    pub fn turn(&mut self, player: usize, dir: Direction) -> bool {
+        match self.players.get_mut(player) {
+            Some(player) if player.alive && self.outcome.is_none() => player.snake.turn(dir),
+            _ => false,
+        }
+    }
+
+    /// Advances every living snake by one cell. `inputs` holds an optional turn per player.
+    pub fn step(&mut self, inputs: &[Option<Direction>]) -> Option<RoundOutcome> {
+        if self.outcome.is_some() {
+            return self.outcome;
+        }
+
+        for (i, dir) in inputs.iter().enumerate() {
+            if let Some(dir) = *dir {
+                self.turn(i, dir);
+            }
+        }
+
+        for player in self.players.iter_mut().filter(|player| player.alive) {
+            player.snake.update(&self.food, &self.config.boundary, &self.obstacles);
+        }
+        self.tick += 1;
+
+        if self.speed_effect.is_some_and(|(_, until)| self.tick >= until) {
+            self.speed_effect = None;
+            self.update_tick_rate();
+        }
+
+        let crashed: Vec<bool> = (0..self.players.len()).map(|i| self.hit_other(i)).collect();
+        for (player, crashed) in self.players.iter_mut().zip(crashed) {
+            if crashed {
+                player.snake.touched = Some(Touched::Snake);
+            }
+        }
+
+        let mut respawn = self.food.expires_at.is_some_and(|expires_at| self.tick >= expires_at);
+        for i in 0..self.players.len() {
+            let player = &mut self.players[i];
+            match player.snake.touched {
+                _ if !player.alive => {}
+                Some(Touched::Food) => {
+                    self.eat(i);
+                    respawn = true;
+                }
+                Some(_) => player.alive = false,
+                None => {}
+            }
+        }
+
+        let alive: Vec<usize> = (0..self.players.len()).filter(|&i| self.players[i].alive).collect();
+        match alive[..] {
+            [] => self.outcome = Some(RoundOutcome::Draw),
+            [winner] if self.players.len() > 1 => self.outcome = Some(RoundOutcome::Winner(winner)),
+            _ => {}
+        }
+
+        if respawn && self.outcome.is_none() {
+            match free_cell(&mut self.rng, &self.players, &self.obstacles) {
+                Some(pos) => self.food = new_food(&mut self.rng, &self.config, pos, self.tick),
+                None => self.outcome = Some(self.leader(&alive)),
+            }
+        }
+        self.outcome
+    }
+
+    /// Whether player `i` moved its head into another living snake this tick.
+    fn hit_other(&self, i: usize) -> bool {
+        let player = &self.players[i];
+        if !player.alive || !matches!(player.snake.touched, None | Some(Touched::Food)) {
+            return false;
+        }
+
+        let head = player.snake.head.0;
+        self.players.iter().enumerate().filter(|&(j, other)| j != i && other.alive).any(|(_, other)| {
+            if other.snake.head.0 == head {
+                player.snake.length() <= other.snake.length()
+            } else {
+                other.snake.occupies(head)
+            }
+        })
+    }
+
+    fn leader(&self, alive: &[usize]) -> RoundOutcome {
+        let best = alive.iter().map(|&i| self.players[i].score).max();
+        let leaders: Vec<_> = alive.iter().filter(|&&i| Some(self.players[i].score) == best).collect();
+        match leaders[..] {
+            [&winner] => RoundOutcome::Winner(winner),
+            _ => RoundOutcome::Draw,
+        }
+    }
+
+    fn eat(&mut self, i: usize) {
+        let kind = self.food.kind;
+        let player = &mut self.players[i];
+        player.score += kind.points();
+        self.eaten += 1;
+
+        match kind {
+            FoodKind::Shrinking => player.snake.shrink(SHRINK_SEGMENTS + 1),
+            FoodKind::SpeedUp => self.speed_effect = Some((SPEED_EFFECT_DELTA, self.tick + SPEED_EFFECT_TICKS)),
+            FoodKind::SlowDown => self.speed_effect = Some((-SPEED_EFFECT_DELTA, self.tick + SPEED_EFFECT_TICKS)),
+            FoodKind::Normal | FoodKind::Golden | FoodKind::Bonus => {}
+        }
+        self.update_tick_rate();
+    }
+
+    fn update_tick_rate(&mut self) {
+        let rate = self.config.speed.tick_rate(self.config.speed.level(self.eaten)) as i32;
+        let delta = self.speed_effect.map_or(0, |(delta, _)| delta);
+        self.tick_rate = (rate + delta).max(1) as u32;
+    }
+}
+
+/// The free cell closest to `preferred` where a snake facing `facing` fits, tail included.
+fn spawn(config: &Config, taken: &OccupancyGrid, preferred: Position, facing: Direction) -> Option<Position> {
+    let (width, height) = config.board;
+    let mut cells: Vec<Position> = (0..height).flat_map(|y| (0..width).map(move |x| Position::new(x, y))).collect();
+    cells.sort_by_key(|pos| (pos.x - preferred.x).abs() + (pos.y - preferred.y).abs());
+    cells.into_iter().find(|&head| {
+        let tail = config.boundary.advance(head, facing.inverse(), config.board);
+        !taken.contains(head) && tail.is_some_and(|tail| !taken.contains(tail))
+    })
+}
+
+fn free_cell(rng: &mut Rand32, players: &[Player], obstacles: &OccupancyGrid) -> Option<Position> {
+    let mut occupancy = obstacles.clone();
+    for player in players.iter().filter(|player| player.alive) {
+        occupancy.union(player.snake.occupancy());
+    }
+    if occupancy.free() == 0 {
+        return None;
+    }
+
+    let nth = rng.rand_range(0..occupancy.free() as u32) as usize;
+    occupancy.nth_free(nth)
+}
+
+/// A best-of match: rounds are replayed on fresh boards until one player has won
+/// `rounds` of them. Drawn rounds count for nobody.
+#[derive(Clone)]
+pub struct Match {
+    seed: u64,
+    config: Config,
+    rounds: u32,
+    wins: Vec<u32>,
+    round: u32,
+    versus: Versus,
+}
+
+impl Match {
+    pub fn new(seed: u64, config: Config, players: usize, rounds: u32) -> Result<Self, String> {
+        let versus = Versus::new(seed, config.clone(), players)?;
+        Ok(Self { seed, config, rounds, wins: vec![0; players], round: 1, versus })
+    }
+
+    pub fn versus(&self) -> &Versus {
+        &self.versus
+    }
+
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    pub fn wins(&self) -> &[u32] {
+        &self.wins
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        self.wins.iter().position(|&wins| wins >= self.rounds)
+    }
+
+    pub fn turn(&mut self, player: usize, dir: Direction) -> bool {
+        self.versus.turn(player, dir)
+    }
+
+    pub fn step(&mut self, inputs: &[Option<Direction>]) -> Option<RoundOutcome> {
+        if self.versus.outcome().is_some() {
+            return self.versus.outcome();
+        }
+
+        let outcome = self.versus.step(inputs);
+        if let Some(RoundOutcome::Winner(winner)) = outcome {
+            self.wins[winner] += 1;
+        }
+        outcome
+    }
+
+    /// Starts the next round once the current one is over and nobody has won the match.
+    pub fn next_round(&mut self) -> bool {
+        if self.versus.outcome().is_none() || self.winner().is_some() {
+            return false;
+        }
+
+        self.round += 1;
+        let seed = self.seed.wrapping_add(self.round as u64 - 1);
+        // Spawns only depend on the config, so they fit again if they did in the first round.
+        self.versus = Versus::new(seed, self.config.clone(), self.wins.len()).expect("spawns fit in the first round");
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Boundary, Level};
+
+    const BOARD: (i16, i16) = (10, 10);
+
+    fn config(rows: &[&str]) -> Config {
+        let level = Level::parse("test", &rows.join("\n")).unwrap();
+        Config { board: level.size, level: Some(level), ..Config::default() }
+    }
+
+    /// A snake of `length` whose tail starts at `start`, grown by eating its way along `dir`.
+    fn grown(start: Position, dir: Direction, length: usize) -> Snake {
+        let mut snake = Snake::new(start, dir, BOARD, 3);
+        for _ in 2..length {
+            let food = Food::new(Position::new_from_move(snake.head.0, dir, BOARD), FoodKind::Normal);
+            snake.update(&food, &Boundary::default(), &OccupancyGrid::new(BOARD.0, BOARD.1));
+        }
+        assert_eq!(snake.length(), length);
+        snake
+    }
+
+    fn arena(versus: &mut Versus, snakes: Vec<Snake>, food: Position) {
+        versus.players = snakes.into_iter().map(|snake| Player { snake, score: 0, alive: true }).collect();
+        versus.food = Food::new(food, FoodKind::Normal);
+    }
+
+    /// Two snakes on row 5 whose heads meet at (4, 5) on the next tick.
+    fn duel(versus: &mut Versus, lengths: [usize; 2], food: Position) {
+        let left = grown(Position::new(5 - lengths[0] as i16, 5), Direction::Right, lengths[0]);
+        let right = grown(Position::new(3 + lengths[1] as i16, 5), Direction::Left, lengths[1]);
+        assert_eq!((left.head.0, right.head.0), (Position::new(3, 5), Position::new(5, 5)));
+        arena(versus, vec![left, right], food);
+    }
+
+    fn open_board() -> Versus {
+        Versus::new(1, Config { board: BOARD, ..Config::default() }, 2).unwrap()
+    }
+
+    #[test]
+    fn spawns_clear_of_walls() {
+        let mut rows = vec![".........."; 10];
+        rows[1] = "....>.....";
+        rows[3] = "..#.......";
+        rows[6] = ".......##.";
+        let config = config(&rows);
+        let versus = Versus::new(1, config, 2).unwrap();
+
+        let heads: Vec<_> = versus.players().iter().map(|player| player.snake.head.0).collect();
+        assert_eq!(heads[0], Position::new(4, 1));
+        let mut taken = versus.obstacles().clone();
+        for player in versus.players() {
+            for segment in player.snake.segments() {
+                assert!(!taken.contains(segment.0), "{:?} spawned on {:?}", heads, segment.0);
+                taken.insert(segment.0);
+            }
+        }
+    }
+
+    #[test]
+    fn fails_without_room_to_spawn() {
+        let config = config(&["###", ".>.", "###"]);
+        assert!(Versus::new(1, config.clone(), 2).is_err());
+        assert!(Match::new(1, config, 2, 3).is_err());
+    }
+
+    #[test]
+    fn shorter_snake_loses_a_head_on_collision() {
+        let mut versus = open_board();
+        duel(&mut versus, [4, 2], Position::new(9, 0));
+        assert_eq!(versus.step(&[None, None]), Some(RoundOutcome::Winner(0)));
+        assert!(versus.players()[0].alive);
+        assert!(!versus.players()[1].alive);
+
+        let mut versus = open_board();
+        duel(&mut versus, [2, 3], Position::new(9, 0));
+        assert_eq!(versus.step(&[None, None]), Some(RoundOutcome::Winner(1)));
+    }
+
+    #[test]
+    fn equal_snakes_both_die_head_on() {
+        let mut versus = open_board();
+        duel(&mut versus, [3, 3], Position::new(9, 0));
+        assert_eq!(versus.step(&[None, None]), Some(RoundOutcome::Draw));
+        assert!(versus.players().iter().all(|player| !player.alive));
+    }
+
+    #[test]
+    fn only_the_snake_running_into_a_body_dies() {
+        let mut versus = open_board();
+        let runner = Snake::new(Position::new(3, 5), Direction::Right, BOARD, 3);
+        // Heading up with its head at (4, 4), so (4, 5) is still body after it moves.
+        let wall = grown(Position::new(4, 6), Direction::Up, 4);
+        arena(&mut versus, vec![runner, wall], Position::new(9, 0));
+
+        assert_eq!(versus.step(&[None, None]), Some(RoundOutcome::Winner(1)));
+        assert_eq!(versus.players()[0].snake.touched, Some(Touched::Snake));
+        assert!(versus.players()[1].alive);
+        assert_eq!(versus.players()[1].snake.head.0, Position::new(4, 3));
+    }
+
+    #[test]
+    fn contested_food_goes_to_the_longer_snake() {
+        let mut versus = open_board();
+        duel(&mut versus, [4, 3], Position::new(4, 5));
+        assert_eq!(versus.step(&[None, None]), Some(RoundOutcome::Winner(0)));
+        assert_eq!(versus.players()[0].score, FoodKind::Normal.points());
+        assert_eq!(versus.players()[0].snake.length(), 5);
+        assert_eq!(versus.players()[1].score, 0);
+
+        let mut versus = open_board();
+        duel(&mut versus, [3, 3], Position::new(4, 5));
+        assert_eq!(versus.step(&[None, None]), Some(RoundOutcome::Draw));
+        assert!(versus.players().iter().all(|player| player.score == 0));
+    }
+
+    #[test]
+    fn match_counts_wins_until_someone_has_enough() {
+        let mut game = Match::new(1, Config { board: BOARD, ..Config::default() }, 2, 2).unwrap();
+        assert!(!game.next_round(), "the first round is still being played");
+
+        duel(&mut game.versus, [4, 2], Position::new(9, 0));
+        assert_eq!(game.step(&[None, None]), Some(RoundOutcome::Winner(0)));
+        assert_eq!(game.step(&[None, None]), Some(RoundOutcome::Winner(0)));
+        assert_eq!((game.wins(), game.winner()), (&[1, 0][..], None));
+
+        assert!(game.next_round());
+        assert_eq!(game.round(), 2);
+        assert_eq!(game.versus().outcome(), None);
+        duel(&mut game.versus, [3, 3], Position::new(9, 0));
+        assert_eq!(game.step(&[None, None]), Some(RoundOutcome::Draw));
+        assert_eq!(game.wins(), [1, 0]);
+
+        assert!(game.next_round());
+        assert_eq!(game.round(), 3);
+        duel(&mut game.versus, [4, 2], Position::new(9, 0));
+        game.step(&[None, None]);
+        assert_eq!((game.wins(), game.winner()), (&[2, 0][..], Some(0)));
+        assert!(!game.next_round());
+        assert_eq!(game.round(), 3);
+    }
+}
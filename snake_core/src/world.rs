@@ -0,0 +1,393 @@
+use std::{collections::VecDeque, time::Duration};
+
+use oorandom::Rand32;
+
+use crate::{Config, View, Direction, Food, FoodKind, OccupancyGrid, Position, Snake, Touched};
+
+pub(crate) const SHRINK_SEGMENTS: usize = 3;
+pub(crate) const SPEED_EFFECT_DELTA: i32 = 4;
+pub(crate) const SPEED_EFFECT_TICKS: u64 = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepOutcome {
+    Moved,
+    Ate,
+    Died(Touched),
+    Won,
+    Over,
+}
+
+#[derive(Clone)]
+pub struct World {
+    seed: u64,
+    config: Config,
+    rng: Rand32,
+    snake: Snake,
+    food: Food,
+    obstacles: OccupancyGrid,
+    fixed_food: VecDeque<Position>,
+    over: bool,
+    won: bool,
+    tick: u64,
+    score: u32,
+    eaten: u32,
+    level: u32,
+    tick_rate: u32,
+    speed_effect: Option<(i32, u64)>,
+    elapsed: Duration,
+}
+
+impl World {
+    /// Worlds built from the same seed and fed the same inputs play out identically.
+    pub fn new(seed: u64) -> Self {
+        World::with_config(seed, Config::default())
+    }
+
+    pub fn with_config(seed: u64, config: Config) -> Self {
+        let mut rng = Rand32::new(seed);
+
+        let board = config.board;
+        let mut obstacles = OccupancyGrid::new(board.0, board.1);
+        let mut fixed_food = VecDeque::new();
+        if let Some(level) = &config.level {
+            level.walls.iter().for_each(|&wall| obstacles.insert(wall));
+            fixed_food.extend(level.food.iter().copied());
+        }
+        let (spawn, facing) = spawn(&config);
+        let snake = Snake::new(spawn, facing, board, config.input_depth);
+
+        let pos = free_cell(&mut rng, &snake, &obstacles, &mut fixed_food).expect("board has room for food");
+        let food = new_food(&mut rng, &config, pos, 0);
+
+        let tick_rate = config.speed.tick_rate(1);
+
+        Self {
+            seed,
+            config,
+            rng,
+            snake,
+            food,
+            obstacles,
+            fixed_food,
+            over: false,
+            won: false,
+            tick: 0,
+            score: 0,
+            eaten: 0,
+            level: 1,
+            tick_rate,
+            speed_effect: None,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Starts a new game from `seed` with the same config, reusing this world's
+    /// allocations. Plays out exactly like `World::with_config(seed, config)`.
+    pub fn reset(&mut self, seed: u64) {
+        let (spawn, facing) = spawn(&self.config);
+        self.snake.reset(spawn, facing);
+        self.fixed_food.clear();
+        if let Some(level) = &self.config.level {
+            self.fixed_food.extend(level.food.iter().copied());
+        }
+
+        self.rng = Rand32::new(seed);
+        let pos = free_cell(&mut self.rng, &self.snake, &self.obstacles, &mut self.fixed_food).expect("board has room for food");
+        self.food = new_food(&mut self.rng, &self.config, pos, 0);
+
+        self.seed = seed;
+        self.over = false;
+        self.won = false;
+        self.tick = 0;
+        self.score = 0;
+        self.eaten = 0;
+        self.level = 1;
+        self.tick_rate = self.config.speed.tick_rate(1);
+        self.speed_effect = None;
+        self.elapsed = Duration::ZERO;
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn snake(&self) -> &Snake {
+        &self.snake
+    }
+
+    pub fn food(&self) -> &Food {
+        &self.food
+    }
+
+    pub fn obstacles(&self) -> &OccupancyGrid {
+        &self.obstacles
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.won
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    /// Ticks per second the world should currently be stepped at.
+    pub fn tick_rate(&self) -> u32 {
+        self.tick_rate
+    }
+
+    /// Game time played so far, accounting for the tick rate of every step taken.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn view(&self) -> View<'_> {
+        View {
+            boundary: &self.config.boundary,
+            obstacles: &self.obstacles,
+            snake: &self.snake,
+            others: Vec::new(),
+            food: &self.food,
+            tick: self.tick,
+        }
+    }
+
+    pub fn turn(&mut self, dir: Direction) -> bool {
+        !self.over && self.snake.turn(dir)
+    }
+
+    /// Moves the food, for tests that need it in a known place.
+    #[cfg(test)]
+    pub(crate) fn place_food(&mut self, food: Food) {
+        self.food = food;
+    }
+
+    pub fn step(&mut self, input: Option<Direction>) -> StepOutcome {
+        if self.over {
+            return StepOutcome::Over;
+        }
+
+        if let Some(dir) = input {
+            self.snake.turn(dir);
+        }
+
+        self.snake.update(&self.food, &self.config.boundary, &self.obstacles);
+        self.tick += 1;
+        self.elapsed += Duration::from_secs(1) / self.tick_rate;
+
+        if self.speed_effect.is_some_and(|(_, until)| self.tick >= until) {
+            self.speed_effect = None;
+            self.update_tick_rate();
+        }
+
+        let outcome = match self.snake.touched {
+            Some(Touched::Food) => {
+                self.eat();
+                StepOutcome::Ate
+            }
+            Some(touched) => {
+                self.over = true;
+                return StepOutcome::Died(touched);
+            }
+            None if self.food.expires_at.is_some_and(|expires_at| self.tick >= expires_at) => StepOutcome::Moved,
+            None => return StepOutcome::Moved,
+        };
+
+        match free_cell(&mut self.rng, &self.snake, &self.obstacles, &mut self.fixed_food) {
+            Some(pos) => {
+                self.food = new_food(&mut self.rng, &self.config, pos, self.tick);
+                outcome
+            }
+            None => {
+                self.over = true;
+                self.won = true;
+                StepOutcome::Won
+            }
+        }
+    }
+
+    fn eat(&mut self) {
+        let kind = self.food.kind;
+        self.score += kind.points();
+        self.eaten += 1;
+        self.level = self.config.speed.level(self.eaten);
+
+        match kind {
+            FoodKind::Shrinking => self.snake.shrink(SHRINK_SEGMENTS + 1),
+            FoodKind::SpeedUp => self.speed_effect = Some((SPEED_EFFECT_DELTA, self.tick + SPEED_EFFECT_TICKS)),
+            FoodKind::SlowDown => self.speed_effect = Some((-SPEED_EFFECT_DELTA, self.tick + SPEED_EFFECT_TICKS)),
+            FoodKind::Normal | FoodKind::Golden | FoodKind::Bonus => {}
+        }
+        self.update_tick_rate();
+    }
+
+    fn update_tick_rate(&mut self) {
+        let rate = self.config.speed.tick_rate(self.level) as i32;
+        let delta = self.speed_effect.map_or(0, |(delta, _)| delta);
+        self.tick_rate = (rate + delta).max(1) as u32;
+    }
+}
+
+fn spawn(config: &Config) -> (Position, Direction) {
+    match &config.level {
+        Some(level) => (level.spawn, level.facing),
+        None => ((config.board.0 / 4, config.board.1 / 2).into(), Direction::Right),
+    }
+}
+
+pub(crate) fn new_food(rng: &mut Rand32, config: &Config, pos: Position, tick: u64) -> Food {
+    let mut food = Food::new(pos, config.food.pick(rng));
+    if food.kind == FoodKind::Bonus {
+        food.expires_at = Some(tick + config.bonus_ticks);
+    }
+    food
+}
+
+fn free_cell(
+    rng: &mut Rand32,
+    snake: &Snake,
+    obstacles: &OccupancyGrid,
+    fixed_food: &mut VecDeque<Position>,
+) -> Option<Position> {
+    while let Some(pos) = fixed_food.pop_front() {
+        if !snake.occupies(pos) {
+            return Some(pos);
+        }
+    }
+
+    let occupancy = snake.occupancy();
+    let free = occupancy.free_with(obstacles);
+    if free == 0 {
+        return None;
+    }
+
+    let nth = rng.rand_range(0..free as u32) as usize;
+    occupancy.nth_free_with(obstacles, nth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Autopilot, Controller, FoodWeights};
+
+    /// Plays `world` under the autopilot, returning every food placement and the final score.
+    fn play(world: &mut World) -> (Vec<(Position, FoodKind)>, u32) {
+        let mut pilot = Autopilot;
+        let mut foods = vec![(world.food().pos, world.food().kind)];
+        while !world.is_over() && world.tick() < 2_000 {
+            let dir = pilot.steer(&world.view());
+            if world.step(dir) == StepOutcome::Ate {
+                foods.push((world.food().pos, world.food().kind));
+            }
+        }
+        (foods, world.score())
+    }
+
+    /// Puts food of `kind` right in front of the snake and steps onto it, then parks
+    /// the next food in the corner, away from the snake's row.
+    fn feed(world: &mut World, kind: FoodKind) {
+        let ahead = Position::new_from_move(world.snake().head.0, world.snake().dir, world.config().board);
+        world.place_food(Food::new(ahead, kind));
+        assert_eq!(world.step(None), StepOutcome::Ate);
+        world.place_food(Food::new(Position::new(0, 0), FoodKind::Normal));
+    }
+
+    fn base_rate(world: &World) -> u32 {
+        world.config().speed.tick_rate(world.level())
+    }
+
+    #[test]
+    fn same_seed_and_inputs_play_out_identically() {
+        let config = Config { board: (12, 10), food: FoodWeights::varied(), ..Config::default() };
+        for seed in 0..10 {
+            let first = play(&mut World::with_config(seed, config.clone()));
+            assert!(first.0.len() > 3, "seed {} ate too little to compare", seed);
+            assert_eq!(first, play(&mut World::with_config(seed, config.clone())), "seed {}", seed);
+
+            let mut reused = World::with_config(seed + 100, config.clone());
+            play(&mut reused);
+            reused.reset(seed);
+            assert_eq!(first, play(&mut reused), "seed {} after reset", seed);
+        }
+    }
+
+    #[test]
+    fn different_seeds_place_food_differently() {
+        let config = Config { board: (12, 10), ..Config::default() };
+        let (first, _) = play(&mut World::with_config(1, config.clone()));
+        let (second, _) = play(&mut World::with_config(2, config));
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn scores_each_kind_of_food() {
+        for kind in FoodKind::ALL {
+            let mut world = World::new(1);
+            feed(&mut world, kind);
+            assert_eq!(world.score(), kind.points(), "{:?}", kind);
+        }
+        assert_eq!((FoodKind::Normal.points(), FoodKind::Golden.points(), FoodKind::Bonus.points()), (1, 5, 3));
+    }
+
+    #[test]
+    fn shrinking_food_takes_three_segments_but_leaves_two() {
+        let mut world = World::new(1);
+        for _ in 0..5 {
+            feed(&mut world, FoodKind::Normal);
+        }
+        assert_eq!(world.snake().length(), 7);
+
+        feed(&mut world, FoodKind::Shrinking);
+        assert_eq!(world.snake().length(), 7 - SHRINK_SEGMENTS);
+        feed(&mut world, FoodKind::Shrinking);
+        assert_eq!(world.snake().length(), 2);
+    }
+
+    #[test]
+    fn speed_effects_wear_off() {
+        for (kind, delta) in [(FoodKind::SpeedUp, SPEED_EFFECT_DELTA), (FoodKind::SlowDown, -SPEED_EFFECT_DELTA)] {
+            let mut world = World::new(1);
+            feed(&mut world, kind);
+            let boosted = (base_rate(&world) as i32 + delta) as u32;
+            assert_eq!(world.tick_rate(), boosted, "{:?}", kind);
+
+            for _ in 1..SPEED_EFFECT_TICKS {
+                assert_eq!(world.step(None), StepOutcome::Moved);
+            }
+            assert_eq!(world.tick_rate(), boosted, "{:?} wore off early", kind);
+            world.step(None);
+            assert_eq!(world.tick_rate(), base_rate(&world), "{:?} never wore off", kind);
+        }
+    }
+
+    #[test]
+    fn expired_bonus_food_moves() {
+        let mut world = World::new(1);
+        let mut bonus = Food::new(Position::new(0, 0), FoodKind::Bonus);
+        bonus.expires_at = Some(world.tick() + 3);
+        world.place_food(bonus);
+
+        world.step(None);
+        world.step(None);
+        assert_eq!(*world.food(), bonus);
+        assert_eq!(world.step(None), StepOutcome::Moved);
+        assert_ne!(*world.food(), bonus);
+        assert_eq!(world.score(), 0);
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    fmt,
+    ops::Range,
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+    time::Duration,
+};
+
+use snake_core::{open_bot, parse_board, Ai, Config, Controller, StepOutcome, Touched, World, DEFAULT_BOT_TIMEOUT};
+
+const USAGE: &str = "usage: snake-sim [--ai autopilot|hamiltonian] [--seeds <start>..<end>] [--threads <n>] \
+[--board <width>x<height>] [--boundary wrap|walls|<tblr>] [--food classic|varied|<kind>=<weight>,...] [--starve <ticks>] \
+[--bot <command>] [--bot-timeout <ms>]";
+
+struct Options {
+    ai: Ai,
+    seeds: Range<u64>,
+    threads: usize,
+    config: Config,
+    /// Ticks without eating after which a game counts as starved. Defaults to twice the
+    /// number of cells, enough for any pilot that sweeps the board.
+    starve: Option<u64>,
+    /// External bot played instead of `ai`, started afresh for every game.
+    bot: Option<String>,
+    bot_timeout: Duration,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Ending {
+    Died(Touched),
+    Starved,
+    Won,
+}
+
+impl fmt::Display for Ending {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ending::Died(Touched::Body) => f.pad("body"),
+            Ending::Died(Touched::Wall) => f.pad("wall"),
+            Ending::Died(Touched::Obstacle) => f.pad("obstacle"),
+            Ending::Died(Touched::Snake) => f.pad("snake"),
+            Ending::Died(Touched::Food) => f.pad("food"),
+            Ending::Starved => f.pad("starved"),
+            Ending::Won => f.pad("won"),
+        }
+    }
+}
+
+struct Game {
+    score: u32,
+    length: usize,
+    ticks: u64,
+    ending: Ending,
+}
+
+fn value<T: FromStr>(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<T, String> {
+    let value = args.next().ok_or_else(|| format!("{} expects a value", flag))?;
+    value.parse().map_err(|_| format!("invalid value for {}: {}", flag, value))
+}
+
+fn parse_seeds(seeds: &str) -> Option<Range<u64>> {
+    let (start, end) = seeds.split_once("..")?;
+    let range = start.parse().ok()?..end.parse().ok()?;
+    (!range.is_empty()).then_some(range)
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options {
+            ai: Ai::Autopilot,
+            seeds: 0..1000,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            config: Config::default(),
+            starve: None,
+            bot: None,
+            bot_timeout: DEFAULT_BOT_TIMEOUT,
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--ai" => options.ai = value(&mut args, "--ai")?,
+                "--seeds" => {
+                    let seeds: String = value(&mut args, "--seeds")?;
+                    options.seeds = parse_seeds(&seeds).ok_or_else(|| format!("invalid value for --seeds: {}", seeds))?;
+                }
+                "--threads" => match value(&mut args, "--threads")? {
+                    0 => return Err("invalid value for --threads: 0".to_string()),
+                    threads => options.threads = threads,
+                },
+                "--board" => {
+                    let board: String = value(&mut args, "--board")?;
+                    options.config.board = parse_board(&board).ok_or_else(|| format!("invalid value for --board: {}", board))?;
+                }
+                "--boundary" => options.config.boundary = value(&mut args, "--boundary")?,
+                "--food" => {
+                    let food: String = value(&mut args, "--food")?;
+                    options.config.food = food.parse().map_err(|err| format!("invalid value for --food: {}", err))?;
+                }
+                "--starve" => options.starve = Some(value(&mut args, "--starve")?),
+                "--bot" => options.bot = Some(value(&mut args, "--bot")?),
+                "--bot-timeout" => options.bot_timeout = Duration::from_millis(value(&mut args, "--bot-timeout")?),
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+
+        options.config.validate()?;
+        Ok(options)
+    }
+
+    fn starve(&self) -> u64 {
+        self.starve.unwrap_or(2 * self.config.board.0 as u64 * self.config.board.1 as u64)
+    }
+}
+
+fn play(seed: u64, options: &Options) -> Game {
+    let mut world = World::with_config(seed, options.config.clone());
+    let mut controller: Box<dyn Controller> = match &options.bot {
+        Some(command) => match open_bot(command, options.bot_timeout) {
+            Ok(bot) => bot,
+            Err(err) => {
+                eprintln!("failed to start bot {}: {}", command, err);
+                std::process::exit(1);
+            }
+        },
+        None => options.ai.controller(),
+    };
+    let starve = options.starve();
+    let mut last_meal = 0;
+
+    let ending = loop {
+        let dir = controller.steer(&world.view());
+        match world.step(dir) {
+            StepOutcome::Ate => last_meal = world.tick(),
+            StepOutcome::Died(touched) => break Ending::Died(touched),
+            StepOutcome::Won => break Ending::Won,
+            StepOutcome::Moved | StepOutcome::Over => {}
+        }
+        if world.tick() - last_meal >= starve {
+            break Ending::Starved;
+        }
+    };
+
+    controller.end(&world.view());
+    Game { score: world.score(), length: world.snake().length(), ticks: world.tick(), ending }
+}
+
+/// Plays every seed in `options.seeds`, handing seeds out to worker threads as they
+/// finish so slow games do not hold up a whole batch.
+fn run(options: &Options) -> Vec<Game> {
+    let next = AtomicU64::new(options.seeds.start);
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..options.threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut games = Vec::new();
+                    loop {
+                        let seed = next.fetch_add(1, Ordering::Relaxed);
+                        if seed >= options.seeds.end {
+                            break games;
+                        }
+                        games.push(play(seed, options));
+                    }
+                })
+            })
+            .collect();
+        workers.into_iter().flat_map(|worker| worker.join().expect("simulation thread panicked")).collect()
+    })
+}
+
+fn mean(values: &[u64]) -> f64 {
+    values.iter().sum::<u64>() as f64 / values.len() as f64
+}
+
+fn median(values: &mut [u64]) -> f64 {
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) as f64 / 2.0
+    } else {
+        values[mid] as f64
+    }
+}
+
+fn summarize(name: &str, mut values: Vec<u64>) {
+    let max = values.iter().max().copied().unwrap_or_default();
+    println!("{:<8} mean {:>10.1}   median {:>10.1}   max {:>8}", name, mean(&values), median(&mut values), max);
+}
+
+fn report(options: &Options, games: &[Game]) {
+    let config = &options.config;
+    println!(
+        "{} games, seeds {}..{}, {} on {}x{} {}, {} threads",
+        games.len(),
+        options.seeds.start,
+        options.seeds.end,
+        options.bot.as_deref().map_or_else(|| options.ai.to_string(), |bot| format!("bot {}", bot)),
+        config.board.0,
+        config.board.1,
+        config.boundary,
+        options.threads
+    );
+
+    summarize("score", games.iter().map(|game| game.score as u64).collect());
+    summarize("length", games.iter().map(|game| game.length as u64).collect());
+    summarize("ticks", games.iter().map(|game| game.ticks).collect());
+
+    let mut endings: Vec<(Ending, usize)> = Vec::new();
+    for game in games {
+        match endings.iter_mut().find(|(ending, _)| *ending == game.ending) {
+            Some((_, count)) => *count += 1,
+            None => endings.push((game.ending, 1)),
+        }
+    }
+    endings.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    for (ending, count) in endings {
+        println!("{:<8} {:>6}   {:>5.1}%", ending, count, 100.0 * count as f64 / games.len() as f64);
+    }
+}
+
+fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let games = run(&options);
+    report(&options, &games);
+}
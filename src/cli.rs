@@ -0,0 +1,173 @@
+use std::{fmt, path::PathBuf, str::FromStr, time::Duration};
+
+use snake_core::{parse_board, Ai, Config, Level, Versus, DEFAULT_BOT_TIMEOUT};
+
+pub const DEFAULT_ROUNDS: u32 = 3;
+
+pub const USAGE: &str = "usage: snake_game [--seed <u64>] [--record <file>] [--replay <file>] [--name <name>] \
+[--boundary wrap|walls|<tblr>] [--board <width>x<height>] [--cell <pixels>] [--input-depth <n>] \
+[--min-speed <ticks/s>] [--max-speed <ticks/s>] [--speed-step <ticks/s>] [--level-every <food>] [--level <file>] \
+[--food classic|varied|<kind>=<weight>,...] [--bonus-ticks <n>] \
+[--versus] [--rounds <wins>] [--opponent <ai>] [--pilot <ai>] \
+[--bot <command>] [--bot-timeout <ms>] [--tui]";
+
+#[derive(Debug)]
+pub struct Options {
+    pub seed: Option<u64>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub name: Option<String>,
+    pub config: Config,
+    pub cell: Option<u32>,
+    /// Rounds needed to win a two player match, set when `--versus` is given.
+    pub rounds: Option<u32>,
+    /// Computer player steering player two in versus matches.
+    pub opponent: Option<Ai>,
+    /// Computer player for the autopilot, which starts switched on when this is set.
+    pub pilot: Option<Ai>,
+    /// Commands for external bots steering player one and, if given twice, player two.
+    pub bots: Vec<String>,
+    pub bot_timeout: Duration,
+    /// Play in the terminal instead of opening a window.
+    pub tui: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            record: None,
+            replay: None,
+            name: None,
+            config: Config::default(),
+            cell: None,
+            rounds: None,
+            opponent: None,
+            pilot: None,
+            bots: Vec::new(),
+            bot_timeout: DEFAULT_BOT_TIMEOUT,
+            tui: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    MissingValue(&'static str),
+    InvalidValue(&'static str, String),
+    Unknown(String),
+    Config(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::MissingValue(flag) => write!(f, "{} expects a value", flag),
+            CliError::InvalidValue(flag, value) => write!(f, "invalid value for {}: {}", flag, value),
+            CliError::Unknown(arg) => write!(f, "unknown argument: {}", arg),
+            CliError::Config(message) => f.write_str(message),
+        }
+    }
+}
+
+fn value<T: FromStr>(args: &mut impl Iterator<Item = String>, flag: &'static str) -> Result<T, CliError> {
+    let value = args.next().ok_or(CliError::MissingValue(flag))?;
+    value.parse().map_err(|_| CliError::InvalidValue(flag, value))
+}
+
+impl Options {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, CliError> {
+        let mut options = Options::default();
+        let mut board_set = false;
+        let mut versus = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => options.seed = Some(value(&mut args, "--seed")?),
+                "--record" => options.record = Some(value(&mut args, "--record")?),
+                "--replay" => options.replay = Some(value(&mut args, "--replay")?),
+                "--name" => options.name = Some(value(&mut args, "--name")?),
+                "--boundary" => options.config.boundary = value(&mut args, "--boundary")?,
+                "--board" => {
+                    let board: String = value(&mut args, "--board")?;
+                    options.config.board = parse_board(&board).ok_or(CliError::InvalidValue("--board", board))?;
+                    board_set = true;
+                }
+                "--cell" => match value(&mut args, "--cell")? {
+                    0 => return Err(CliError::InvalidValue("--cell", "0".to_string())),
+                    cell => options.cell = Some(cell),
+                },
+                "--input-depth" => match value(&mut args, "--input-depth")? {
+                    0 => return Err(CliError::InvalidValue("--input-depth", "0".to_string())),
+                    depth => options.config.input_depth = depth,
+                },
+                "--min-speed" => options.config.speed.min_rate = value(&mut args, "--min-speed")?,
+                "--max-speed" => options.config.speed.max_rate = value(&mut args, "--max-speed")?,
+                "--speed-step" => options.config.speed.step = value(&mut args, "--speed-step")?,
+                "--level-every" => options.config.speed.food_per_level = value(&mut args, "--level-every")?,
+                "--level" => {
+                    let path: PathBuf = value(&mut args, "--level")?;
+                    let level = Level::load(&path).map_err(|err| CliError::Config(format!("{}:{}", path.display(), err)))?;
+                    options.config.level = Some(level);
+                }
+                "--food" => {
+                    let food: String = value(&mut args, "--food")?;
+                    options.config.food = food.parse().map_err(|err| CliError::InvalidValue("--food", err))?;
+                }
+                "--bonus-ticks" => options.config.bonus_ticks = value(&mut args, "--bonus-ticks")?,
+                "--versus" => versus = true,
+                "--pilot" => options.pilot = Some(value(&mut args, "--pilot")?),
+                "--bot" => {
+                    if options.bots.len() == 2 {
+                        return Err(CliError::Config("at most two --bot commands are supported".to_string()));
+                    }
+                    options.bots.push(value(&mut args, "--bot")?);
+                    versus |= options.bots.len() == 2;
+                }
+                "--bot-timeout" => options.bot_timeout = Duration::from_millis(value(&mut args, "--bot-timeout")?),
+                "--opponent" => {
+                    options.opponent = Some(value(&mut args, "--opponent")?);
+                    versus = true;
+                }
+                "--rounds" => match value(&mut args, "--rounds")? {
+                    0 => return Err(CliError::InvalidValue("--rounds", "0".to_string())),
+                    rounds => options.rounds = Some(rounds),
+                },
+                "--tui" => options.tui = true,
+                _ => return Err(CliError::Unknown(arg)),
+            }
+        }
+
+        if let (Some(level), false) = (&options.config.level, board_set) {
+            options.config.board = level.size;
+        }
+        if options.bots.len() == 2 && options.opponent.is_some() {
+            return Err(CliError::Config("--opponent cannot be combined with a second --bot".to_string()));
+        }
+        if versus {
+            if options.tui {
+                return Err(CliError::Config("--versus is not supported with --tui".to_string()));
+            }
+            if options.record.is_some() || options.replay.is_some() {
+                return Err(CliError::Config("--versus cannot be recorded or replayed".to_string()));
+            }
+            options.rounds = Some(options.rounds.unwrap_or(DEFAULT_ROUNDS));
+        } else if options.rounds.is_some() {
+            return Err(CliError::Config("--rounds requires --versus".to_string()));
+        }
+        options.config.validate().map_err(CliError::Config)?;
+        if options.rounds.is_some() {
+            Versus::new(0, options.config.clone(), 2).map_err(CliError::Config)?;
+        }
+        Ok(options)
+    }
+
+    #[cfg(feature = "window")]
+    pub fn player_name(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| std::env::var("USER").ok())
+            .or_else(|| std::env::var("USERNAME").ok())
+            .unwrap_or_else(|| "player".to_string())
+    }
+}
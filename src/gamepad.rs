@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use ggez::{event::GamepadId, input::gamepad::gilrs};
+use snake_core::Direction;
+
+use crate::keymap::Action;
+
+pub const DEADZONE: f32 = 0.5;
+
+#[derive(Default)]
+struct Stick {
+    x: f32,
+    y: f32,
+    dir: Option<Direction>,
+}
+
+impl Stick {
+    /// Snaps the stick to the dominant axis and returns the direction when it changes.
+    fn update(&mut self) -> Option<Direction> {
+        let dir = if self.x.hypot(self.y) < DEADZONE {
+            None
+        } else if self.x.abs() > self.y.abs() {
+            Some(if self.x > 0.0 { Direction::Right } else { Direction::Left })
+        } else {
+            Some(if self.y > 0.0 { Direction::Up } else { Direction::Down })
+        };
+
+        let changed = dir != self.dir;
+        self.dir = dir;
+        if changed {
+            dir
+        } else {
+            None
+        }
+    }
+}
+
+pub struct Gamepads {
+    players: Vec<Option<GamepadId>>,
+    sticks: HashMap<GamepadId, Stick>,
+}
+
+impl Gamepads {
+    pub fn new(players: usize) -> Self {
+        Self { players: vec![None; players], sticks: HashMap::new() }
+    }
+
+    /// Returns the player `id` is assigned to, claiming the first free player slot
+    /// for controllers that have not been seen before.
+    pub fn player(&mut self, id: GamepadId) -> Option<usize> {
+        if let Some(player) = self.players.iter().position(|slot| *slot == Some(id)) {
+            return Some(player);
+        }
+        let player = self.players.iter().position(Option::is_none)?;
+        self.players[player] = Some(id);
+        Some(player)
+    }
+
+    pub fn release(&mut self, id: GamepadId) {
+        for slot in self.players.iter_mut().filter(|slot| **slot == Some(id)) {
+            *slot = None;
+        }
+        self.sticks.remove(&id);
+    }
+
+    pub fn action(button: gilrs::Button) -> Option<Action> {
+        match button {
+            gilrs::Button::DPadUp => Some(Action::Up),
+            gilrs::Button::DPadDown => Some(Action::Down),
+            gilrs::Button::DPadLeft => Some(Action::Left),
+            gilrs::Button::DPadRight => Some(Action::Right),
+            gilrs::Button::Start => Some(Action::Pause),
+            gilrs::Button::Select => Some(Action::Restart),
+            gilrs::Button::East => Some(Action::Quit),
+            gilrs::Button::RightTrigger | gilrs::Button::RightTrigger2 => Some(Action::SpeedUp),
+            gilrs::Button::North => Some(Action::Autopilot),
+            _ => None,
+        }
+    }
+
+    pub fn axis(&mut self, id: GamepadId, axis: gilrs::Axis, value: f32) -> Option<Direction> {
+        let stick = self.sticks.entry(id).or_default();
+        match axis {
+            gilrs::Axis::LeftStickX => stick.x = value,
+            gilrs::Axis::LeftStickY => stick.y = value,
+            _ => return None,
+        }
+        stick.update()
+    }
+
+    pub fn describe(&self) -> String {
+        self.players
+            .iter()
+            .enumerate()
+            .map(|(player, slot)| match slot {
+                Some(_) => format!("Player {}: controller", player + 1),
+                None => format!("Player {}: keyboard (press a controller button to join)", player + 1),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,148 @@
+use std::{
+    cmp::Reverse,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const HEADER: &str = "snake-highscores 1";
+pub const MAX_ENTRIES: usize = 10;
+
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub name: String,
+    pub score: u32,
+    pub length: usize,
+    pub seed: u64,
+    pub mode: String,
+    pub timestamp: u64,
+}
+
+impl Entry {
+    pub fn new(name: &str, score: u32, length: usize, seed: u64, mode: &str) -> Self {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        Self { name: sanitize(name), score, length, seed, mode: sanitize(mode), timestamp }
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(6, '\t');
+        let score = fields.next()?.parse().ok()?;
+        let length = fields.next()?.parse().ok()?;
+        let seed = fields.next()?.parse().ok()?;
+        let mode = fields.next()?.to_string();
+        let timestamp = fields.next()?.parse().ok()?;
+        let name = fields.next()?.to_string();
+        Some(Self { name, score, length, seed, mode, timestamp })
+    }
+
+    fn to_line(&self) -> String {
+        format!("{}\t{}\t{}\t{}\t{}\t{}", self.score, self.length, self.seed, self.mode, self.timestamp, self.name)
+    }
+}
+
+pub struct HighScores {
+    path: PathBuf,
+    entries: Vec<Entry>,
+}
+
+impl HighScores {
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = match fs::read_to_string(&path) {
+            Ok(contents) => parse(&path, &contents),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                eprintln!("failed to read high scores from {}: {}", path.display(), err);
+                Vec::new()
+            }
+        };
+        Self { path, entries }
+    }
+
+    pub fn insert(&mut self, entry: Entry) -> Option<usize> {
+        let rank = self.entries.iter().position(|other| entry.score > other.score).unwrap_or(self.entries.len());
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut contents = format!("{}\n", HEADER);
+        for entry in &self.entries {
+            contents.push_str(&entry.to_line());
+            contents.push('\n');
+        }
+
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, contents)?;
+        fs::rename(&tmp, &self.path)
+    }
+
+    pub fn table(&self) -> String {
+        if self.entries.is_empty() {
+            return "No high scores yet".to_string();
+        }
+
+        let mut table = String::from("High scores\n");
+        for (rank, entry) in self.entries.iter().enumerate() {
+            table.push_str(&format!(
+                "{:>2}. {:<12} {:>5}  len {:<4} {:<8} {}  seed {}\n",
+                rank + 1,
+                entry.name,
+                entry.score,
+                entry.length,
+                entry.mode,
+                format_date(entry.timestamp),
+                entry.seed,
+            ));
+        }
+        table
+    }
+}
+
+fn parse(path: &Path, contents: &str) -> Vec<Entry> {
+    let mut lines = contents.lines();
+    if lines.next() != Some(HEADER) {
+        eprintln!("ignoring unrecognized high score file {}", path.display());
+        return Vec::new();
+    }
+
+    let mut entries: Vec<Entry> = lines
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let entry = Entry::parse(line);
+            if entry.is_none() {
+                eprintln!("skipping corrupt high score entry in {}: {:?}", path.display(), line);
+            }
+            entry
+        })
+        .collect();
+    entries.sort_by_key(|entry| Reverse(entry.score));
+    entries.truncate(MAX_ENTRIES);
+    entries
+}
+
+fn sanitize(field: &str) -> String {
+    field.chars().map(|c| if c.is_control() { ' ' } else { c }).collect()
+}
+
+// civil date from days since the unix epoch, see http://howardhinnant.github.io/date_algorithms.html
+fn format_date(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
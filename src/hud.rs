@@ -0,0 +1,42 @@
+use ggez::graphics;
+use snake_core::{Match, World};
+
+pub const HUD_HEIGHT: f32 = 32.0;
+
+pub fn draw(canvas: &mut graphics::Canvas, world: &World, tick_rate: u32, width: f32) {
+    let seconds = world.elapsed().as_secs();
+    let text = graphics::Text::new(format!(
+        "Level {}    Score {}    Length {}    Time {}:{:02}    Speed {}/s",
+        world.level(),
+        world.score(),
+        world.snake().length(),
+        seconds / 60,
+        seconds % 60,
+        tick_rate,
+    ));
+    draw_bar(canvas, text, width);
+}
+
+pub fn draw_versus(canvas: &mut graphics::Canvas, versus: &Match, tick_rate: u32, width: f32) {
+    let players: Vec<_> = versus
+        .versus()
+        .players()
+        .iter()
+        .zip(versus.wins())
+        .enumerate()
+        .map(|(i, (player, wins))| format!("P{} {} ({} won)", i + 1, player.score, wins))
+        .collect();
+    let text = graphics::Text::new(format!(
+        "Round {}    {}    First to {}    Speed {}/s",
+        versus.round(),
+        players.join("    "),
+        versus.rounds(),
+        tick_rate,
+    ));
+    draw_bar(canvas, text, width);
+}
+
+fn draw_bar(canvas: &mut graphics::Canvas, text: graphics::Text, width: f32) {
+    canvas.draw(&graphics::Quad, graphics::DrawParam::new().dest_rect(graphics::Rect::new(0.0, 0.0, width, HUD_HEIGHT)).color([0.15, 0.15, 0.15, 1.0]));
+    canvas.draw(&text, graphics::DrawParam::new().dest([8.0, 8.0]).color([1.0, 1.0, 1.0, 1.0]));
+}
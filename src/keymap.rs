@@ -0,0 +1,247 @@
+use std::{collections::HashMap, fmt, fs, io, path::Path, str::FromStr};
+
+use ggez::input::keyboard::KeyCode;
+use snake_core::Direction;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Pause,
+    Restart,
+    Quit,
+    SpeedUp,
+    Autopilot,
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Pause,
+        Action::Restart,
+        Action::Quit,
+        Action::SpeedUp,
+        Action::Autopilot,
+    ];
+
+    pub fn direction(&self) -> Option<Direction> {
+        match self {
+            Action::Up => Some(Direction::Up),
+            Action::Down => Some(Direction::Down),
+            Action::Left => Some(Direction::Left),
+            Action::Right => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Pause => "pause",
+            Action::Restart => "restart",
+            Action::Quit => "quit",
+            Action::SpeedUp => "speed_up",
+            Action::Autopilot => "autopilot",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Action {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Action::ALL.into_iter().find(|action| action.name() == s).ok_or(())
+    }
+}
+
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {
+        const KEY_NAMES: &[(&str, KeyCode)] = &[$((stringify!($key), KeyCode::$key)),*];
+    };
+}
+
+key_names!(
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    Up, Down, Left, Right, Space, Return, Escape, Tab, Back, Delete, Insert, Home, End, PageUp, PageDown,
+    LShift, RShift, LControl, RControl, LAlt, RAlt, Comma, Period, Slash, Semicolon, Minus, Equals,
+);
+
+pub fn key_name(key: KeyCode) -> String {
+    KEY_NAMES
+        .iter()
+        .find(|(_, code)| *code == key)
+        .map_or_else(|| format!("{:?}", key), |(name, _)| name.to_string())
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    KEY_NAMES.iter().find(|(known, _)| known.eq_ignore_ascii_case(name)).map(|(_, code)| *code)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    Arrows,
+    Wasd,
+    Hjkl,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 3] = [Preset::Arrows, Preset::Wasd, Preset::Hjkl];
+
+    fn directions(&self) -> [KeyCode; 4] {
+        match self {
+            Preset::Arrows => [KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right],
+            Preset::Wasd => [KeyCode::W, KeyCode::S, KeyCode::A, KeyCode::D],
+            Preset::Hjkl => [KeyCode::K, KeyCode::J, KeyCode::H, KeyCode::L],
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Preset::Arrows => "arrows",
+            Preset::Wasd => "wasd",
+            Preset::Hjkl => "hjkl",
+        })
+    }
+}
+
+impl FromStr for Preset {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Preset::ALL.into_iter().find(|preset| preset.to_string() == s).ok_or(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<KeyCode, Action>,
+}
+
+impl Keymap {
+    pub fn preset(preset: Preset) -> Self {
+        let [up, down, left, right] = preset.directions();
+        let bindings = HashMap::from([
+            (up, Action::Up),
+            (down, Action::Down),
+            (left, Action::Left),
+            (right, Action::Right),
+            (KeyCode::P, Action::Pause),
+            (KeyCode::Return, Action::Restart),
+            (KeyCode::Escape, Action::Quit),
+            (KeyCode::Space, Action::SpeedUp),
+            (KeyCode::O, Action::Autopilot),
+        ]);
+        Self { bindings }
+    }
+
+    /// Loads bindings from `path`, falling back to the arrow preset when the file is
+    /// missing. Malformed lines are reported and skipped.
+    pub fn load(path: &Path) -> Self {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                if err.kind() != io::ErrorKind::NotFound {
+                    eprintln!("failed to read key bindings from {}: {}", path.display(), err);
+                }
+                return Keymap::default();
+            }
+        };
+
+        let mut keymap = Keymap::default();
+        for (n, line) in contents.lines().enumerate() {
+            if let Err(message) = keymap.apply_line(line) {
+                eprintln!("{}:{}: {}", path.display(), n + 1, message);
+            }
+        }
+        keymap
+    }
+
+    fn apply_line(&mut self, line: &str) -> Result<(), String> {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            return Ok(());
+        }
+
+        let (key, value) = line.split_once('=').ok_or("expected <action> = <keys>")?;
+        let (key, value) = (key.trim(), value.trim());
+
+        if key == "preset" {
+            let preset = value.parse().map_err(|_| format!("unknown preset {:?}", value))?;
+            *self = Keymap::preset(preset);
+            return Ok(());
+        }
+
+        let action: Action = key.parse().map_err(|_| format!("unknown action {:?}", key))?;
+        let keys = value
+            .split(',')
+            .map(|name| parse_key(name.trim()).ok_or_else(|| format!("unknown key {:?}", name.trim())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.bindings.retain(|_, bound| *bound != action);
+        for key in keys {
+            if let Some(other) = self.bindings.insert(key, action) {
+                return Err(format!("{} was bound to {}, now bound to {}", key_name(key), other, action));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut contents = String::new();
+        for action in Action::ALL {
+            contents.push_str(&format!("{} = {}\n", action, self.keys(action).join(", ")));
+        }
+        fs::write(path, contents)
+    }
+
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        self.bindings.get(&key).copied()
+    }
+
+    pub fn keys(&self, action: Action) -> Vec<String> {
+        let mut keys: Vec<_> = self.bindings.iter().filter(|(_, bound)| **bound == action).map(|(key, _)| key_name(*key)).collect();
+        keys.sort();
+        keys
+    }
+
+    /// Makes `key` the only binding for `action`, unless another action already uses it.
+    pub fn rebind(&mut self, action: Action, key: KeyCode) -> Result<(), Action> {
+        match self.action(key) {
+            Some(other) if other != action => Err(other),
+            _ => {
+                self.bindings.retain(|_, bound| *bound != action);
+                self.bindings.insert(key, action);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::preset(Preset::Arrows)
+    }
+}
@@ -0,0 +1,39 @@
+use ggez::graphics;
+use snake_core::Position;
+
+use crate::hud::HUD_HEIGHT;
+
+pub const DEFAULT_CELL: u32 = 32;
+const FIT_MARGIN: f32 = 0.9;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Layout {
+    pub board: (i16, i16),
+    pub cell: f32,
+}
+
+impl Layout {
+    pub fn new(board: (i16, i16), cell: u32) -> Self {
+        Self { board, cell: cell as f32 }
+    }
+
+    pub fn screen(&self) -> (f32, f32) {
+        (self.board.0 as f32 * self.cell, self.board.1 as f32 * self.cell + HUD_HEIGHT)
+    }
+
+    pub fn board_rect(&self) -> graphics::Rect {
+        graphics::Rect::new(0.0, HUD_HEIGHT, self.board.0 as f32 * self.cell, self.board.1 as f32 * self.cell)
+    }
+
+    pub fn cell_rect(&self, pos: Position) -> graphics::Rect {
+        graphics::Rect::new(pos.x as f32 * self.cell, pos.y as f32 * self.cell + HUD_HEIGHT, self.cell, self.cell)
+    }
+
+    /// Shrinks the cell size so the whole window fits on a display of the given logical size.
+    pub fn fit(self, display: (f32, f32)) -> Self {
+        let max_width = display.0 * FIT_MARGIN / self.board.0 as f32;
+        let max_height = (display.1 * FIT_MARGIN - HUD_HEIGHT) / self.board.1 as f32;
+        let cell = self.cell.min(max_width).min(max_height).floor().max(1.0);
+        Self { cell, ..self }
+    }
+}
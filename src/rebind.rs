@@ -0,0 +1,62 @@
+use ggez::input::keyboard::KeyCode;
+
+use crate::keymap::{key_name, Action, Keymap, Preset};
+
+#[derive(Default)]
+pub struct RebindScreen {
+    selected: usize,
+    capturing: bool,
+    message: Option<String>,
+}
+
+impl RebindScreen {
+    /// Handles a key press on the rebinding screen and returns whether the screen should close.
+    pub fn key_down(&mut self, keymap: &mut Keymap, key: KeyCode) -> bool {
+        let action = Action::ALL[self.selected];
+
+        if self.capturing {
+            self.capturing = false;
+            self.message = match key {
+                KeyCode::Escape => None,
+                key => match keymap.rebind(action, key) {
+                    Ok(()) => None,
+                    Err(other) => Some(format!("{} is already bound to {}", key_name(key), other)),
+                },
+            };
+            return false;
+        }
+
+        self.message = None;
+        match key {
+            KeyCode::Up => self.selected = (self.selected + Action::ALL.len() - 1) % Action::ALL.len(),
+            KeyCode::Down => self.selected = (self.selected + 1) % Action::ALL.len(),
+            KeyCode::Return => self.capturing = true,
+            KeyCode::F1 | KeyCode::F2 | KeyCode::F3 => {
+                let preset = match key {
+                    KeyCode::F1 => Preset::Arrows,
+                    KeyCode::F2 => Preset::Wasd,
+                    _ => Preset::Hjkl,
+                };
+                *keymap = Keymap::preset(preset);
+                self.message = Some(format!("loaded {} preset", preset));
+            }
+            KeyCode::Escape => return true,
+            _ => {}
+        }
+        false
+    }
+
+    pub fn text(&self, keymap: &Keymap) -> String {
+        let mut text = String::from("Key bindings\n\n");
+        for (i, action) in Action::ALL.into_iter().enumerate() {
+            let cursor = if i == self.selected { ">" } else { " " };
+            let keys = if i == self.selected && self.capturing { "press a key...".to_string() } else { keymap.keys(action).join(", ") };
+            text.push_str(&format!("{} {:<10} {}\n", cursor, action, keys));
+        }
+        text.push_str("\nUp/Down - select   Enter - rebind   F1/F2/F3 - arrows/wasd/hjkl   Esc - back\n");
+        if let Some(message) = &self.message {
+            text.push_str(&format!("\n{}\n", message));
+        }
+        text
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+    terminal::{self, ClearType},
+};
+use snake_core::{Controller, Direction, Edge, FoodKind, Position, Replay};
+
+use crate::{cli::Options, Session};
+
+const HEAD: Color = Color::AnsiValue(208);
+const BODY: Color = Color::AnsiValue(172);
+const WALL: Color = Color::AnsiValue(245);
+const FLOOR: Color = Color::AnsiValue(238);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Key {
+    Steer(Direction),
+    Pause,
+    Restart,
+    Autopilot,
+    Quit,
+}
+
+impl Key {
+    fn from_event(event: KeyEvent) -> Option<Self> {
+        if event.kind == KeyEventKind::Release {
+            return None;
+        }
+        let key = match event.code {
+            KeyCode::Char('c') if event.modifiers.contains(KeyModifiers::CONTROL) => Key::Quit,
+            KeyCode::Up | KeyCode::Char('w' | 'W') => Key::Steer(Direction::Up),
+            KeyCode::Down | KeyCode::Char('s' | 'S') => Key::Steer(Direction::Down),
+            KeyCode::Left | KeyCode::Char('a' | 'A') => Key::Steer(Direction::Left),
+            KeyCode::Right | KeyCode::Char('d' | 'D') => Key::Steer(Direction::Right),
+            KeyCode::Char('p' | 'P' | ' ') => Key::Pause,
+            KeyCode::Enter | KeyCode::Char('r' | 'R') => Key::Restart,
+            KeyCode::Char('o' | 'O') => Key::Autopilot,
+            KeyCode::Esc | KeyCode::Char('q' | 'Q') => Key::Quit,
+            _ => return None,
+        };
+        Some(key)
+    }
+}
+
+/// Puts the terminal in raw mode on the alternate screen until dropped.
+struct Terminal;
+
+impl Terminal {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let terminal = Terminal;
+        crossterm::execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide, terminal::Clear(ClearType::All))?;
+        Ok(terminal)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = crossterm::execute!(io::stdout(), ResetColor, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn food_color(kind: FoodKind) -> Color {
+    match kind {
+        FoodKind::Normal => Color::White,
+        FoodKind::Golden => Color::AnsiValue(220),
+        FoodKind::Shrinking => Color::AnsiValue(129),
+        FoodKind::SpeedUp => Color::AnsiValue(196),
+        FoodKind::SlowDown => Color::AnsiValue(27),
+        FoodKind::Bonus => Color::AnsiValue(40),
+    }
+}
+
+struct Tui {
+    session: Session,
+    paused: bool,
+}
+
+impl Tui {
+    fn start(&mut self) {
+        self.session.start();
+        self.paused = false;
+    }
+
+    /// Draws the whole screen, two columns per cell so the board looks square.
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let config = self.session.world.config();
+        let (width, height) = config.board;
+        let edge = |dir: Direction, wall: &'static str| if config.boundary.edge(dir) == Edge::Wall { wall } else { " " };
+
+        let seconds = self.session.world.elapsed().as_secs();
+        queue!(
+            out,
+            cursor::MoveTo(0, 0),
+            ResetColor,
+            terminal::Clear(ClearType::CurrentLine),
+            Print(format!(
+                "Level {}  Score {}  Length {}  Time {}:{:02}  Speed {}/s{}",
+                self.session.world.level(),
+                self.session.world.score(),
+                self.session.world.snake().length(),
+                seconds / 60,
+                seconds % 60,
+                self.session.world.tick_rate(),
+                if self.session.autopilot { "  [autopilot]" } else { "" },
+            )),
+        )?;
+
+        let horizontal = |dir| format!("+{}+", edge(dir, "-").repeat(width as usize * 2));
+        queue!(out, cursor::MoveTo(0, 1), SetForegroundColor(WALL), Print(horizontal(Direction::Up)))?;
+        for y in 0..height {
+            queue!(out, cursor::MoveTo(0, y as u16 + 2), SetForegroundColor(WALL), Print(edge(Direction::Left, "|")))?;
+            for x in 0..width {
+                let pos = Position::new(x, y);
+                let food = self.session.world.food();
+                let (color, cell) = if pos == self.session.world.snake().head.0 {
+                    (HEAD, "@@")
+                } else if self.session.world.snake().occupies(pos) {
+                    (BODY, "()")
+                } else if pos == food.pos {
+                    (food_color(food.kind), "<>")
+                } else if self.session.world.obstacles().contains(pos) {
+                    (WALL, "##")
+                } else {
+                    (FLOOR, " .")
+                };
+                queue!(out, SetForegroundColor(color), Print(cell))?;
+            }
+            queue!(out, SetForegroundColor(WALL), Print(edge(Direction::Right, "|")))?;
+        }
+        queue!(out, cursor::MoveTo(0, height as u16 + 2), Print(horizontal(Direction::Down)), ResetColor)?;
+
+        let status = if self.session.world.is_won() {
+            format!("You win! Board filled in {} ticks. Enter - restart, q - quit", self.session.world.tick())
+        } else if self.session.world.is_over() {
+            format!("Game over, seed {}. Enter - restart, q - quit", self.session.world.seed())
+        } else if self.paused {
+            "Paused. p - resume, Enter - restart, q - quit".to_string()
+        } else {
+            "Arrows/WASD - steer, p - pause, o - autopilot, q - quit".to_string()
+        };
+        queue!(out, cursor::MoveTo(0, height as u16 + 3), terminal::Clear(ClearType::CurrentLine), Print(status))?;
+        out.flush()
+    }
+}
+
+/// Plays in the terminal until the player quits, using the same `World` as the window.
+pub fn run(options: Options, replay: Option<Replay>, pilot: Option<Box<dyn Controller + Send>>) -> io::Result<()> {
+    let board = replay.as_ref().map_or(options.config.board, |replay| replay.config.board);
+    let needed = (board.0 as u16 * 2 + 2, board.1 as u16 + 4);
+    let (columns, rows) = terminal::size()?;
+    if columns < needed.0 || rows < needed.1 {
+        return Err(io::Error::other(format!(
+            "a {}x{} board needs a {}x{} terminal, this one is {}x{}",
+            board.0, board.1, needed.0, needed.1, columns, rows
+        )));
+    }
+
+    let mut tui = Tui { session: Session::new(&options, replay, pilot), paused: false };
+    tui.start();
+
+    let _terminal = Terminal::enter()?;
+    let mut out = io::stdout();
+    let mut next_tick = Instant::now();
+
+    loop {
+        tui.draw(&mut out)?;
+
+        let timeout = next_tick.saturating_duration_since(Instant::now());
+        if !event::poll(timeout)? {
+            if !tui.paused && !tui.session.world.is_over() {
+                tui.session.tick();
+            }
+            next_tick = Instant::now() + Duration::from_secs(1) / tui.session.world.tick_rate();
+            continue;
+        }
+
+        let key = match event::read()? {
+            Event::Key(event) => Key::from_event(event),
+            Event::Resize(..) => {
+                queue!(out, terminal::Clear(ClearType::All))?;
+                None
+            }
+            _ => None,
+        };
+        match key {
+            Some(Key::Quit) => {
+                tui.session.end();
+                return Ok(());
+            }
+            Some(Key::Restart) if tui.paused || tui.session.world.is_over() => tui.start(),
+            Some(Key::Pause) if !tui.session.world.is_over() => tui.paused = !tui.paused,
+            Some(Key::Autopilot) if !tui.session.is_playback() => tui.session.autopilot = !tui.session.autopilot,
+            Some(Key::Steer(dir)) if !tui.paused => tui.session.steer(dir),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,532 @@
+use std::path::PathBuf;
+
+use ggez::{
+    event::GamepadId,
+    graphics,
+    input::{gamepad::gilrs, keyboard::KeyCode},
+    Context,
+};
+use snake_core::{Boundary, Config, Controller, Direction, Edge, Food, FoodKind, FoodWeights, Match, Replay, RoundOutcome, Snake};
+
+use crate::{
+    cli,
+    gamepad::Gamepads,
+    highscores::{Entry, HighScores},
+    hud,
+    keymap::{Action, Keymap, Preset},
+    layout::{self, Layout},
+    random_seed,
+    rebind::RebindScreen,
+    Session,
+};
+
+const PLAYER_COLORS: [[f32; 4]; 2] = [[1.0, 0.5, 0.0, 1.0], [0.0, 0.8, 0.9, 1.0]];
+
+fn draw_walls(canvas: &mut graphics::Canvas, layout: &Layout, boundary: &Boundary) {
+    let thickness = (layout.cell / 8.0).max(1.0);
+    let graphics::Rect { x, y, w, h } = layout.board_rect();
+
+    let walls = [
+        (boundary.top, graphics::Rect::new(x, y, w, thickness)),
+        (boundary.bottom, graphics::Rect::new(x, y + h - thickness, w, thickness)),
+        (boundary.left, graphics::Rect::new(x, y, thickness, h)),
+        (boundary.right, graphics::Rect::new(x + w - thickness, y, thickness, h)),
+    ];
+    for (edge, rect) in walls {
+        if edge == Edge::Wall {
+            canvas.draw(&graphics::Quad, graphics::DrawParam::new().dest_rect(rect).color([0.5, 0.5, 0.5, 1.0]));
+        }
+    }
+}
+
+fn draw_obstacles(canvas: &mut graphics::Canvas, layout: &Layout, config: &Config) {
+    if let Some(level) = &config.level {
+        for &wall in &level.walls {
+            canvas.draw(&graphics::Quad, graphics::DrawParam::new().dest_rect(layout.cell_rect(wall)).color([0.5, 0.5, 0.5, 1.0]));
+        }
+    }
+}
+
+trait Draw {
+    fn draw(&self, canvas: &mut graphics::Canvas, layout: &Layout);
+}
+
+impl Draw for Food {
+    fn draw(&self, canvas: &mut graphics::Canvas, layout: &Layout) {
+        let color = match self.kind {
+            FoodKind::Normal => [1.0, 1.0, 1.0, 1.0],
+            FoodKind::Golden => [1.0, 0.84, 0.0, 1.0],
+            FoodKind::Shrinking => [0.6, 0.2, 0.8, 1.0],
+            FoodKind::SpeedUp => [0.9, 0.1, 0.1, 1.0],
+            FoodKind::SlowDown => [0.2, 0.4, 1.0, 1.0],
+            FoodKind::Bonus => [0.1, 0.8, 0.2, 1.0],
+        };
+        canvas.draw(&graphics::Quad, graphics::DrawParam::new().dest_rect(layout.cell_rect(self.pos)).color(color));
+    }
+}
+
+impl Draw for Snake {
+    fn draw(&self, canvas: &mut graphics::Canvas, layout: &Layout) {
+        draw_snake(canvas, layout, self, PLAYER_COLORS[0]);
+    }
+}
+
+fn draw_snake(canvas: &mut graphics::Canvas, layout: &Layout, snake: &Snake, color: [f32; 4]) {
+    for segment in snake.segments() {
+        canvas.draw(&graphics::Quad, graphics::DrawParam::new().dest_rect(layout.cell_rect(segment.0)).color(color));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Scene {
+    Menu,
+    Playing,
+    Paused,
+    GameOver,
+    Bindings,
+}
+
+struct GameState {
+    scene: Scene,
+    cell: u32,
+    layout: Layout,
+    session: Session,
+    rounds: Option<u32>,
+    versus: Option<Match>,
+    versus_keys: [Keymap; 2],
+    opponent: Option<Box<dyn Controller + Send>>,
+    assisted: bool,
+    name: String,
+    highscores: HighScores,
+    rank: Option<usize>,
+    keymap: Keymap,
+    keymap_path: PathBuf,
+    rebind: RebindScreen,
+    boost: bool,
+    gamepads: Gamepads,
+}
+
+impl GameState {
+    fn new(
+        ctx: &mut Context,
+        options: cli::Options,
+        replay: Option<Replay>,
+        bots: Vec<Box<dyn Controller + Send>>,
+        highscores: HighScores,
+        keymap_path: PathBuf,
+    ) -> Self {
+        let name = options.player_name();
+        let mut bots = bots.into_iter();
+        let session = Session::new(&options, replay, bots.next());
+        let players = if options.rounds.is_some() { 2 } else { 1 };
+        let cell = options.cell.unwrap_or(layout::DEFAULT_CELL);
+
+        let mut state = Self {
+            scene: Scene::Menu,
+            cell,
+            layout: Layout::new(session.world.config().board, cell),
+            session,
+            rounds: options.rounds,
+            versus: None,
+            versus_keys: [Keymap::preset(Preset::Arrows), Keymap::preset(Preset::Wasd)],
+            opponent: bots.next().or_else(|| options.opponent.map(|ai| ai.controller())),
+            assisted: false,
+            name,
+            highscores,
+            rank: None,
+            keymap: Keymap::load(&keymap_path),
+            keymap_path,
+            rebind: RebindScreen::default(),
+            boost: false,
+            gamepads: Gamepads::new(players),
+        };
+        state.resize(ctx);
+        state
+    }
+
+    fn resize(&mut self, ctx: &mut Context) {
+        let mut layout = Layout::new(self.config().board, self.cell);
+        if let Some(monitor) = ctx.gfx.window().current_monitor() {
+            let size = monitor.size();
+            layout = layout.fit((size.width as f32, size.height as f32));
+        }
+
+        let (width, height) = layout.screen();
+        if ctx.gfx.drawable_size() != (width, height) {
+            if let Err(err) = ctx.gfx.set_drawable_size(width, height) {
+                eprintln!("failed to resize window: {}", err);
+            }
+        }
+        self.layout = layout;
+    }
+
+    fn config(&self) -> &Config {
+        match &self.versus {
+            Some(versus) => versus.versus().config(),
+            None => self.session.world.config(),
+        }
+    }
+
+    /// Tells the controllers the current game is over, whether it ended or was abandoned.
+    fn end_game(&mut self) {
+        match &self.versus {
+            Some(versus) => {
+                let round = versus.versus();
+                self.session.pilot.end(&round.view(0));
+                if let Some(opponent) = &mut self.opponent {
+                    opponent.end(&round.view(1));
+                }
+            }
+            None => self.session.end(),
+        }
+    }
+
+    fn start(&mut self, ctx: &mut Context) {
+        if let Some(rounds) = self.rounds {
+            self.end_game();
+            let seed = self.session.seed.unwrap_or_else(random_seed);
+            let config = self.session.config.clone();
+            self.versus = Some(Match::new(seed, config, 2, rounds).expect("spawns are checked when parsing options"));
+            println!("seed {}", seed);
+            self.resize(ctx);
+            self.boost = false;
+            self.scene = Scene::Playing;
+            return;
+        }
+
+        self.session.start();
+        println!("seed {}", self.session.world.seed());
+        self.resize(ctx);
+        self.boost = false;
+        self.assisted = self.session.autopilot;
+        self.rank = None;
+        self.scene = Scene::Playing;
+    }
+
+    fn tick(&mut self) {
+        if let Some(versus) = &mut self.versus {
+            let round = versus.versus();
+            let pilot = if self.session.autopilot { self.session.pilot.steer(&round.view(0)) } else { None };
+            let opponent = self.opponent.as_mut().and_then(|opponent| opponent.steer(&round.view(1)));
+            if versus.step(&[pilot, opponent]).is_some() {
+                self.end_game();
+                self.scene = Scene::GameOver;
+            }
+            return;
+        }
+
+        self.session.tick();
+        let world = &self.session.world;
+        if world.is_over() {
+            if world.is_won() {
+                println!("board filled in {} ticks", world.tick());
+            }
+            if !self.session.is_playback() && !self.assisted {
+                self.submit_score();
+            }
+            self.scene = Scene::GameOver;
+        }
+    }
+
+    fn tick_rate(&self) -> u32 {
+        let rate = match &self.versus {
+            Some(versus) => versus.versus().tick_rate(),
+            None => self.session.world.tick_rate(),
+        };
+        if self.boost {
+            rate * 2
+        } else {
+            rate
+        }
+    }
+
+    /// Starts the next round of a versus match, or a new match once someone has won.
+    fn next_round(&mut self, ctx: &mut Context) {
+        if self.versus.as_mut().is_some_and(Match::next_round) {
+            self.boost = false;
+            self.scene = Scene::Playing;
+        } else {
+            self.start(ctx);
+        }
+    }
+
+    fn back_to_menu(&mut self) {
+        self.end_game();
+        self.scene = Scene::Menu;
+    }
+
+    fn mode(&self) -> String {
+        let config = self.session.world.config();
+        let mode = match &config.level {
+            Some(level) => format!("{} {}", config.boundary, level.name),
+            None => format!("{} {}x{}", config.boundary, config.board.0, config.board.1),
+        };
+        if config.food == FoodWeights::classic() {
+            return mode;
+        }
+        format!("{} {}", mode, config.food)
+    }
+
+    fn submit_score(&mut self) {
+        let world = &self.session.world;
+        let entry = Entry::new(&self.name, world.score(), world.snake().length(), world.seed(), &self.mode());
+        self.rank = self.highscores.insert(entry);
+        if self.rank.is_some() {
+            if let Err(err) = self.highscores.save() {
+                eprintln!("failed to save high scores: {}", err);
+            }
+        }
+    }
+
+    fn steer(&mut self, player: usize, dir: Direction) {
+        if let Some(versus) = &mut self.versus {
+            if player == 0 || self.opponent.is_none() {
+                versus.turn(player, dir);
+            }
+            return;
+        }
+        if player == 0 {
+            self.session.steer(dir);
+        }
+    }
+
+    fn perform(&mut self, ctx: &mut Context, action: Action) {
+        match (self.scene, action) {
+            (Scene::Menu, Action::Restart | Action::Pause) => self.start(ctx),
+            (Scene::Menu, Action::Quit) => ggez::event::request_quit(ctx),
+            (Scene::Playing, Action::Pause) => self.scene = Scene::Paused,
+            (Scene::Playing, Action::SpeedUp) => self.boost = true,
+            (Scene::Playing, Action::Autopilot) if !self.session.is_playback() => {
+                self.session.autopilot = !self.session.autopilot;
+                self.assisted |= self.session.autopilot;
+            }
+            (Scene::Playing, action) => {
+                if let Some(dir) = action.direction() {
+                    self.steer(0, dir);
+                }
+            }
+            (Scene::Paused, Action::Pause) => self.scene = Scene::Playing,
+            (Scene::GameOver, Action::Restart | Action::Pause) if self.versus.is_some() => self.next_round(ctx),
+            (Scene::Paused | Scene::GameOver, Action::Restart) | (Scene::GameOver, Action::Pause) => self.start(ctx),
+            (Scene::Paused | Scene::GameOver, Action::Quit) => self.back_to_menu(),
+            _ => {}
+        }
+    }
+
+    fn keys(&self, action: Action) -> String {
+        self.keymap.keys(action).join("/")
+    }
+
+    fn versus_result(&self, versus: &Match) -> String {
+        let round = match versus.versus().outcome() {
+            Some(RoundOutcome::Winner(winner)) => format!("Round {}: player {} wins", versus.round(), winner + 1),
+            _ => format!("Round {}: draw", versus.round()),
+        };
+        let wins: Vec<_> = versus.wins().iter().map(u32::to_string).collect();
+        let next = match versus.winner() {
+            Some(winner) => format!("Player {} wins the match!\n\n{} - new match", winner + 1, self.keys(Action::Restart)),
+            None => format!("{} - next round", self.keys(Action::Restart)),
+        };
+        format!("{}\nRounds won {}\n\n{}\n{} - menu", round, wins.join(" - "), next, self.keys(Action::Quit))
+    }
+
+    fn draw_message(&self, canvas: &mut graphics::Canvas, message: String) {
+        let text = graphics::Text::new(message);
+        let board = self.layout.board_rect();
+        canvas.draw(&text, graphics::DrawParam::new().dest([board.x + 16.0, board.y + 16.0]).color([1.0, 1.0, 1.0, 1.0]));
+    }
+}
+
+impl ggez::event::EventHandler for GameState {
+    fn update(&mut self, ctx: &mut Context) -> Result<(), ggez::GameError> {
+        while ctx.time.check_update_time(self.tick_rate()) {
+            if self.scene == Scene::Playing {
+                self.tick();
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> Result<(), ggez::GameError> {
+        let mut canvas = graphics::Canvas::from_frame(ctx, graphics::CanvasLoadOp::Clear([0.0, 0.0, 0.0, 1.0].into()));
+
+        if let (Some(versus), false) = (&self.versus, matches!(self.scene, Scene::Menu | Scene::Bindings)) {
+            let round = versus.versus();
+            round.food().draw(&mut canvas, &self.layout);
+            for (player, color) in round.players().iter().zip(PLAYER_COLORS).filter(|(player, _)| player.alive) {
+                draw_snake(&mut canvas, &self.layout, &player.snake, color);
+            }
+            draw_walls(&mut canvas, &self.layout, &round.config().boundary);
+            draw_obstacles(&mut canvas, &self.layout, round.config());
+            hud::draw_versus(&mut canvas, versus, self.tick_rate(), self.layout.screen().0);
+        } else if !matches!(self.scene, Scene::Menu | Scene::Bindings) {
+            let world = &self.session.world;
+            world.food().draw(&mut canvas, &self.layout);
+            world.snake().draw(&mut canvas, &self.layout);
+            draw_walls(&mut canvas, &self.layout, &world.config().boundary);
+            draw_obstacles(&mut canvas, &self.layout, world.config());
+            hud::draw(&mut canvas, world, self.tick_rate(), self.layout.screen().0);
+        }
+
+        match self.scene {
+            Scene::Menu => {
+                let mode = match (&self.session.replay, self.rounds) {
+                    (Some(_), _) => "watch replay",
+                    (None, Some(_)) if self.opponent.is_some() => "start versus (arrows vs computer)",
+                    (None, Some(_)) => "start versus (arrows vs WASD)",
+                    (None, None) => "start",
+                };
+                self.draw_message(
+                    &mut canvas,
+                    format!(
+                        "Snake\n\n{} - {}\n{} - quit\nTab - key bindings\n\n{}\n\n{}",
+                        self.keys(Action::Restart),
+                        mode,
+                        self.keys(Action::Quit),
+                        self.gamepads.describe(),
+                        self.highscores.table()
+                    ),
+                );
+            }
+            Scene::Bindings => self.draw_message(&mut canvas, self.rebind.text(&self.keymap)),
+            Scene::Playing => {}
+            Scene::Paused => self.draw_message(
+                &mut canvas,
+                format!("Paused\n\n{} - resume\n{} - restart\n{} - menu", self.keys(Action::Pause), self.keys(Action::Restart), self.keys(Action::Quit)),
+            ),
+            Scene::GameOver if self.versus.is_some() => {
+                let message = self.versus.as_ref().map(|versus| self.versus_result(versus)).unwrap_or_default();
+                self.draw_message(&mut canvas, message);
+            }
+            Scene::GameOver => {
+                let world = &self.session.world;
+                let rank = match self.rank {
+                    Some(rank) => format!("New high score! #{}\n", rank + 1),
+                    None => String::new(),
+                };
+                self.draw_message(
+                    &mut canvas,
+                    format!(
+                        "{}\nseed {}\n{}\n{} - restart\n{} - menu\n\n{}",
+                        if world.is_won() { format!("You win! Board filled in {} ticks", world.tick()) } else { "Game over".to_string() },
+                        world.seed(),
+                        rank,
+                        self.keys(Action::Restart),
+                        self.keys(Action::Quit),
+                        self.highscores.table()
+                    ),
+                )
+            }
+        }
+
+        canvas.finish(ctx)?;
+
+        ggez::timer::yield_now();
+        Ok(())
+    }
+
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        input: ggez::input::keyboard::KeyInput,
+        _repeated: bool,
+    ) -> Result<(), ggez::GameError> {
+        let key = match input.keycode {
+            Some(key) => key,
+            None => return Ok(()),
+        };
+
+        if self.scene == Scene::Bindings {
+            if self.rebind.key_down(&mut self.keymap, key) {
+                if let Err(err) = self.keymap.save(&self.keymap_path) {
+                    eprintln!("failed to save key bindings to {}: {}", self.keymap_path.display(), err);
+                }
+                self.scene = Scene::Menu;
+            }
+            return Ok(());
+        }
+
+        if self.scene == Scene::Menu && key == KeyCode::Tab {
+            self.rebind = RebindScreen::default();
+            self.scene = Scene::Bindings;
+            return Ok(());
+        }
+
+        if self.versus.is_some() && self.scene == Scene::Playing {
+            let steer = self.versus_keys.iter().enumerate().find_map(|(player, keys)| Some((player, keys.action(key)?.direction()?)));
+            if let Some((player, dir)) = steer {
+                self.steer(player, dir);
+                return Ok(());
+            }
+        }
+
+        if let Some(action) = self.keymap.action(key) {
+            self.perform(ctx, action);
+        }
+        Ok(())
+    }
+
+    fn key_up_event(&mut self, _ctx: &mut Context, input: ggez::input::keyboard::KeyInput) -> Result<(), ggez::GameError> {
+        if input.keycode.and_then(|key| self.keymap.action(key)) == Some(Action::SpeedUp) {
+            self.boost = false;
+        }
+        Ok(())
+    }
+
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, button: gilrs::Button, id: GamepadId) -> Result<(), ggez::GameError> {
+        let player = match self.gamepads.player(id) {
+            Some(player) => player,
+            None => return Ok(()),
+        };
+
+        match Gamepads::action(button) {
+            Some(Action::Quit) if self.scene == Scene::Menu => self.gamepads.release(id),
+            Some(action) => match (self.scene, action.direction()) {
+                (Scene::Playing, Some(dir)) => self.steer(player, dir),
+                _ => self.perform(ctx, action),
+            },
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn gamepad_button_up_event(&mut self, _ctx: &mut Context, button: gilrs::Button, id: GamepadId) -> Result<(), ggez::GameError> {
+        if self.gamepads.player(id).is_some() && Gamepads::action(button) == Some(Action::SpeedUp) {
+            self.boost = false;
+        }
+        Ok(())
+    }
+
+    fn gamepad_axis_event(&mut self, _ctx: &mut Context, axis: gilrs::Axis, value: f32, id: GamepadId) -> Result<(), ggez::GameError> {
+        let player = match self.gamepads.player(id) {
+            Some(player) => player,
+            None => return Ok(()),
+        };
+
+        if let Some(dir) = self.gamepads.axis(id, axis, value) {
+            if self.scene == Scene::Playing {
+                self.steer(player, dir);
+            }
+        }
+        Ok(())
+    }
+
+    fn quit_event(&mut self, _ctx: &mut Context) -> Result<bool, ggez::GameError> {
+        self.end_game();
+        Ok(false)
+    }
+}
+
+pub fn run(options: cli::Options, replay: Option<Replay>, bots: Vec<Box<dyn Controller + Send>>) {
+    let (mut ctx, event_loop) = ggez::ContextBuilder::new("snake", "suryanshmak")
+        .window_setup(ggez::conf::WindowSetup::default().title("Snake"))
+        .build()
+        .expect("Failed to initialize ggez");
+
+    let highscores = HighScores::load(ctx.fs.user_data_dir().join("highscores.txt"));
+
+    let keymap_path = ctx.fs.user_config_dir().join("keymap.cfg");
+
+    let state = GameState::new(&mut ctx, options, replay, bots, highscores, keymap_path);
+    ggez::event::run(ctx, event_loop, state);
+}